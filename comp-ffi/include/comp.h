@@ -0,0 +1,45 @@
+/* comp - reverse Polish notation (RPN) command interpreter (C bindings) */
+
+#ifndef COMP_H
+#define COMP_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct Comp comp_t;
+
+/* construct an interpreter (release with comp_free) */
+comp_t *comp_new(void);
+
+/* evaluate a whitespace-separated operations list
+   (0 on success, 1 on evaluation error, -1 on invalid argument) */
+int comp_eval(comp_t *comp, const char *ops);
+
+/* number of elements on the stack */
+size_t comp_stack_len(const comp_t *comp);
+
+/* read the stack element at index (0 is the bottom of the stack)
+   (0 on success, -1 on invalid argument or index out of range) */
+int comp_stack_get(const comp_t *comp, size_t index, double *value);
+
+/* number of lines printed (print and pstack) by the last evaluation */
+size_t comp_output_len(const comp_t *comp);
+
+/* printed line at index of the last evaluation (NULL if out of range),
+   valid until the next evaluation */
+const char *comp_output_get(const comp_t *comp, size_t index);
+
+/* message of the last failed evaluation (NULL if it succeeded) */
+const char *comp_last_error(const comp_t *comp);
+
+/* release an interpreter */
+void comp_free(comp_t *comp);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* COMP_H */
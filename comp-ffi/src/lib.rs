@@ -0,0 +1,187 @@
+//! C bindings for the comp interpreter
+//!
+//! The interpreter is exposed to C/C++ host applications as an opaque
+//! `comp_t` handle (see `include/comp.h`).
+//!
+//! ```c
+//! comp_t *comp = comp_new();
+//!
+//! if (comp_eval(comp, "3 dup x print 4 dup x +") == 0) {
+//!   double value;
+//!   comp_stack_get(comp, 0, &value); // 25.0
+//!   puts(comp_output_get(comp, 0)); // 9
+//! } else {
+//!   fprintf(stderr, "error: %s\n", comp_last_error(comp));
+//! }
+//!
+//! comp_free(comp);
+//! ```
+
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::ptr;
+use comp::Config;
+use comp::Interpreter;
+
+/// Interpreter handle (`comp_t`).
+pub struct Comp {
+  cinter: Interpreter,
+  error: Option<CString>,
+  output: Vec<CString>, // lines printed by the last evaluation
+}
+
+/// Construct an interpreter using the built-in defaults. The handle must be
+/// released with `comp_free`.
+#[no_mangle]
+pub extern "C" fn comp_new() -> *mut Comp {
+  match Interpreter::with_config(Config::default()) {
+    Ok(cinter) => Box::into_raw(Box::new(Comp { cinter, error: None, output: Vec::new() })),
+    Err(_) => ptr::null_mut(),
+  }
+}
+
+/// Evaluate a NUL-terminated, whitespace-separated operations list. Returns 0
+/// on success, 1 if evaluation failed (see `comp_last_error`), and -1 if an
+/// argument is invalid.
+///
+/// # Safety
+///
+/// `comp` must be a handle returned by `comp_new`, and `ops` must point to a
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn comp_eval(comp: *mut Comp, ops: *const c_char) -> c_int {
+  if comp.is_null() || ops.is_null() {
+    return -1;
+  }
+  let comp: &mut Comp = &mut *comp;
+
+  let ops: &str = match CStr::from_ptr(ops).to_str() {
+    Ok(ops) => ops,
+    Err(_) => return -1,
+  };
+
+  comp.cinter.push_ops(ops);
+  let result = comp.cinter.process_ops();
+  comp.output = comp.cinter.output.drain(..).filter_map(|line| CString::new(line).ok()).collect();
+  match result {
+    Ok(()) => {
+      comp.error = None;
+      0
+    },
+    Err(error) => {
+      comp.error = CString::new(error.to_string()).ok();
+      1
+    },
+  }
+}
+
+/// Number of elements on the stack.
+///
+/// # Safety
+///
+/// `comp` must be a handle returned by `comp_new`.
+#[no_mangle]
+pub unsafe extern "C" fn comp_stack_len(comp: *const Comp) -> usize {
+  if comp.is_null() {
+    return 0;
+  }
+
+  let comp: &Comp = &*comp;
+
+  comp.cinter.stack.len()
+}
+
+/// Read the stack element at `index` (0 is the bottom of the stack) into
+/// `value`. Returns 0 on success and -1 if an argument is invalid or the
+/// index is out of range.
+///
+/// # Safety
+///
+/// `comp` must be a handle returned by `comp_new`, and `value` must point to
+/// a writable double.
+#[no_mangle]
+pub unsafe extern "C" fn comp_stack_get(comp: *const Comp, index: usize, value: *mut f64) -> c_int {
+  if comp.is_null() || value.is_null() {
+    return -1;
+  }
+
+  let comp: &Comp = &*comp;
+
+  match comp.cinter.stack.get(index) {
+    Some(element) => {
+      *value = element.to_f64();
+      0
+    },
+    None => -1,
+  }
+}
+
+/// Number of lines printed by the `print` and `pstack` commands during the
+/// last evaluation.
+///
+/// # Safety
+///
+/// `comp` must be a handle returned by `comp_new`.
+#[no_mangle]
+pub unsafe extern "C" fn comp_output_len(comp: *const Comp) -> usize {
+  if comp.is_null() {
+    return 0;
+  }
+
+  let comp: &Comp = &*comp;
+
+  comp.output.len()
+}
+
+/// Printed line at `index` of the last evaluation (NULL if an argument is
+/// invalid or the index is out of range). The string is owned by the handle
+/// and valid until the next evaluation.
+///
+/// # Safety
+///
+/// `comp` must be a handle returned by `comp_new`.
+#[no_mangle]
+pub unsafe extern "C" fn comp_output_get(comp: *const Comp, index: usize) -> *const c_char {
+  if comp.is_null() {
+    return ptr::null();
+  }
+
+  let comp: &Comp = &*comp;
+
+  match comp.output.get(index) {
+    Some(line) => line.as_ptr(),
+    None => ptr::null(),
+  }
+}
+
+/// Message of the last failed evaluation (NULL if the last evaluation
+/// succeeded). The string is owned by the handle.
+///
+/// # Safety
+///
+/// `comp` must be a handle returned by `comp_new`.
+#[no_mangle]
+pub unsafe extern "C" fn comp_last_error(comp: *const Comp) -> *const c_char {
+  if comp.is_null() {
+    return ptr::null();
+  }
+
+  let comp: &Comp = &*comp;
+
+  match &comp.error {
+    Some(error) => error.as_ptr(),
+    None => ptr::null(),
+  }
+}
+
+/// Release an interpreter handle.
+///
+/// # Safety
+///
+/// `comp` must be a handle returned by `comp_new` (or NULL), and must not be
+/// used after it is released.
+#[no_mangle]
+pub unsafe extern "C" fn comp_free(comp: *mut Comp) {
+  if !comp.is_null() {
+    drop(Box::from_raw(comp));
+  }
+}
@@ -0,0 +1,43 @@
+//! WebAssembly bindings for the comp interpreter
+//!
+//! Build with `wasm-pack build comp-wasm --target web` (or `cargo build -p
+//! comp-wasm --target wasm32-unknown-unknown`) and call `evaluate` from
+//! JavaScript.
+//!
+//! ```js
+//! import init, { evaluate } from "./pkg/comp_wasm.js";
+//!
+//! await init();
+//! const result = evaluate("3 dup x print 4 dup x +");
+//! result.stack;  // ["25"]
+//! result.output; // ["9"]
+//! ```
+
+use comp::Config;
+use comp::Interpreter;
+use wasm_bindgen::prelude::*;
+
+/// Result of an evaluation.
+#[wasm_bindgen(getter_with_clone)]
+pub struct Evaluation {
+  /// Resulting stack as formatted values (bottom of the stack first).
+  pub stack: Vec<String>,
+  /// Lines printed by the `print` and `pstack` commands.
+  pub output: Vec<String>,
+}
+
+/// Evaluate a whitespace-separated operations list and return the resulting
+/// stack and printed lines.
+#[wasm_bindgen]
+pub fn evaluate(ops: &str) -> Result<Evaluation, JsError> {
+  // configuration files are not available in the browser
+  let mut cinter: Interpreter = Interpreter::with_config(Config::default())?;
+
+  cinter.push_ops(ops);
+  cinter.process_ops()?;
+
+  Ok(Evaluation {
+    stack: cinter.stack.iter().map(|element| cinter.format_value(element)).collect(),
+    output: std::mem::take(&mut cinter.output),
+  })
+}
@@ -1,727 +1,653 @@
 use std::env;
-use std::fs::File;
+use std::ffi::OsStr;
+use std::fs;
 use std::io::prelude::*;
-use std::num::ParseFloatError;
-use std::num::ParseIntError;
+use std::io::IsTerminal;
 use std::path::Path;
-use std::path::Display;
-use std::collections::HashMap;
+use std::path::PathBuf;
+use clap::builder::{PossibleValue, StringValueParser, TypedValueParser};
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
 use colored::*;
+use comp::AngleMode;
+use comp::ColorMode;
+use comp::Config;
+use comp::Format;
+use comp::Interpreter;
+use comp::Rounding;
 
 const RELEASE_STATUS: &str = "i";
 
-/*
-
-    note: base data structure is a vector (linked
-    list) used as a stack. atoms on the list are
-    either be symbols (commands) or values. each
-    calculation is a list of operations that are
-    processed in order of occurrence. this is an
-    implementation of a lisp interpreter for rev-
-    erse polish notation s-expressions (sexp).
-
-      operations list structure
-        (object : command or value)
-        "5"
-        "sqrt"
-        "1
-        "-"
-        "2"
-        "/"
-
-    a list evaluation engine takes the list of
-    strings and executes the corresponding oper-
-    ations then returns the resulting mutated
-    stack.
-
-*/
-
 // -- command list -------------------------------------------------------------
-const CMDS: &str = "drop dup swap cls clr roll rot + +_ - x x_ / chs abs round \
-int inv sqrt throot proot ^ exp % mod ! gcd pi e d_r r_d sin asin cos acos \
-tan atan log log2 log10 ln logn sa .a a sb .b b sc .c c";
-
-
-fn main() {
-  // enable or disable backtrace on error
-  env::set_var("RUST_BACKTRACE", "0");
-
-  // construct command interpreter
-  let mut cinter = Interpreter::new();
-
-  // get command line arguments and collect into a vector
-  let mut args: Vec<String> = env::args().collect();
-
-  // if no arguments are passed, behave as if help flag was passed
-  if args.len() <= 1 {
-    args.push("help".to_string());
-  }
-
-  if args[1] == "--help" || args[1] == "help" {
-    // display command usage information
-    show_help();
-    std::process::exit(0);
-
-  } else if args[1] == "--version" || args[1] == "version" {
-    // display version information
-    show_version();
-    std::process::exit(0);
-
-  } else if args[1] == "mona" {
-    println!("{MONA}");
-    std::process::exit(0);
-
-  } else if args[1] == "-f" || args[1] == "--file" {
-    // read operations list input from file
-    if args.len() > 2 {
-      // read file path
-      let filename: String = args[2].to_string();
-      let path: &Path = Path::new(&filename);
-      let display: Display = path.display();
-
-      // open file
-      let mut file: File = match File::open(&path) {
-        Ok(file) => file,
-        Err(error) => {
-          eprintln!("{}: could not open file [{}]: {error}", "error".bright_red(), display.to_string().cyan());
-          std::process::exit(99);
-        },
-      };
-
-      // read file contents
-      let mut file_contents: String = String::new();
-      match file.read_to_string(&mut file_contents) {
-        Ok(_) => (),
-        Err(error) => {
-          eprintln!("{}: could not read [{}]: {error}", "error".bright_red(), display.to_string().cyan());
-          std::process::exit(99);
-        },
-      };
-
-      // split individual list elements
-      let temp_ops: Vec<&str> = file_contents.split_whitespace().collect();
-
-      // create operations list vector from file contents
-      for op in temp_ops {
-        cinter.ops.push(op.to_string());
-      }
-
-    } else {
-      eprintln!("{}: no file path provided", "error".bright_red());
-      std::process::exit(99);
-
-    }
-
-  } else {
-    // read operations list input from arguments
-    cinter.ops = (&args[1..]).to_vec();
-
-  }
-
-  // process operations list
-  cinter.process_ops();
-
-  // display resulting computation stack
-  for element in cinter.stack {
-    println!("  {}", element.truecolor(0, 192, 255).bold());
-  }
-
-  std::process::exit(0);
+const CMDS: &str = "drop dup swap cls clr roll rot rolln rotn dropn dupn take \
+skip sort sortd rev uniq uniqt over pick swapn nip tuck 2dup 2drop 2swap \
+2over depth count + +_ - x x_ sum mean median var varp sd sdp / // divmod chs \
+abs sign round int roundn inv sqrt hypot throot cbrt nroot proot ^ exp % mod \
+pctchg ! gcd lcm modpow modinv isprime nextprime prevprime factor fib lucas \
+gamma lngamma ncr npr min max min_ max_ clamp lerp invlerp remap pi e d_r r_d \
+dms ddeg sin asin cos acos tan atan atan2 sinh cosh tanh asinh acosh atanh \
+log log2 log10 ln e^ exp2 expm1 ln1p logn re im conj arg mag tofloat tofrac \
+roman unroman chr ord bits frombits mantissa expnt ulp nextafter isnan isinf \
+isfinite and or xor not shl shr < > <= >= == != land lor lnot lxor rand randn \
+randexp randpois randint seed shuffle range linspace hex bin oct dec prec sci \
+fix eng si group ungroup auto print pstack sa .a a sb .b b sc .c c store local \
+recall save map filter call if ifelse while times for fold reduce zipwith zip+ \
+zipx again redo undo keep load wstack rstack include alias";
+
+
+// -- command line interface ---------------------------------------------------
+
+/// comp - command interpreter
+#[derive(Parser)]
+#[command(name = "comp", allow_negative_numbers = true,
+          disable_help_flag = true, disable_help_subcommand = true, disable_version_flag = true)]
+struct Cli {
+  #[command(subcommand)]
+  command: Option<CliCommand>,
+
+  #[command(flatten)]
+  options: Options,
+
+  /// Read operations from a file (repeatable; files are evaluated in order
+  /// before the operations list)
+  #[arg(short = 'f', long = "file", value_name = "FILE")]
+  files: Vec<PathBuf>,
+
+  /// Display help and usage information
+  #[arg(long = "help")]
+  help: bool,
+
+  /// Show version
+  #[arg(long = "version")]
+  version: bool,
+
+  /// Operations list ('-' reads operations from standard input)
+  #[arg(trailing_var_arg = true, allow_hyphen_values = true, value_name = "LIST",
+        value_parser = OpsParser, hide_possible_values = true)]
+  ops: Vec<String>,
 }
 
-struct Function {
-  name: String,
-  fops: Vec<String>,
+#[derive(Subcommand)]
+enum CliCommand {
+  /// Evaluate an operations list
+  Eval {
+    /// Operations list ('-' reads operations from standard input)
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true, value_name = "LIST",
+          value_parser = OpsParser, hide_possible_values = true)]
+    ops: Vec<String>,
+  },
+  /// Evaluate the operations in a file
+  File {
+    /// Path to operations file
+    path: PathBuf,
+  },
+  /// Start an interactive session
+  Repl,
+  /// Step through the operations in a file interactively
+  Debug {
+    /// Path to operations file
+    #[arg(short = 'f', long = "file", value_name = "FILE")]
+    path: PathBuf,
+  },
+  /// Display help and usage information (for a subcommand)
+  Help {
+    /// Subcommand
+    command: Option<String>,
+  },
+  /// Describe a command (arity, stack effect, and example)
+  Describe {
+    /// Command name
+    op: String,
+  },
+  /// Show version
+  Version,
+  /// Generate a shell completion script
+  Completions {
+    /// Shell (bash, elvish, fish, powershell, or zsh)
+    shell: Shell,
+  },
+  #[command(hide = true)]
+  Mona,
 }
 
-struct Interpreter {
-  stack: Vec<String>,
-  mem_a: f64,
-  mem_b: f64,
-  mem_c: f64,
-  ops: Vec<String>,
-  fns: Vec<Function>,
-  cmap: HashMap<String, fn(&mut Interpreter, &str)>,
-}
+// operations list parser (accepts any operation and offers the native command
+// names as completions)
+#[derive(Clone)]
+struct OpsParser;
 
-impl Interpreter {
-  // constructor
-  fn new() -> Interpreter {
-    let mut cint = Interpreter {
-      stack: Vec::new(),
-      mem_a: 0.0,
-      mem_b: 0.0,
-      mem_c: 0.0,
-      ops: Vec::new(),
-      fns: Vec::new(),
-      cmap: HashMap::new(),
-    };
-    cint.init();
-
-    cint
-  }
-
-  // process operations method
-  fn process_ops(&mut self) {
-    while !self.ops.is_empty() {
-      let operation: String = self.ops.remove(0); // pop first operation
-      self.process_node(&operation);
-    }
-  }
+impl TypedValueParser for OpsParser {
+  type Value = String;
 
-  // add native command to interpreter
-  fn compose_native(&mut self, name: &str, func: fn(&mut Interpreter, &str)) {
-    self.cmap.insert(name.to_string(), func);
-  }
-
-  fn init(&mut self) {
-    // stack manipulation
-    self.compose_native("drop",   Interpreter::c_drop);     // drop
-    self.compose_native("dup",    Interpreter::c_dup);      // duplicate
-    self.compose_native("swap",   Interpreter::c_swap);     // swap x and y
-    self.compose_native("cls",    Interpreter::c_cls);      // clear stack
-    self.compose_native("clr",    Interpreter::c_cls);      // clear stack
-    self.compose_native("roll",   Interpreter::c_roll);     // roll stack
-    self.compose_native("rot",    Interpreter::c_rot);      // rotate stack (reverse direction from roll)
-    // memory usage
-    self.compose_native("sa",     Interpreter::c_store_a);  // store (pop value off stack and store)
-    self.compose_native(".a",     Interpreter::c_store_a);  // store (pop value off stack and store)
-    self.compose_native("a",      Interpreter::c_push_a);   // retrieve (push stored value onto the stack)
-    self.compose_native("sb",     Interpreter::c_store_b);  // store
-    self.compose_native(".b",     Interpreter::c_store_b);  // store
-    self.compose_native("b",      Interpreter::c_push_b);   // retrieve
-    self.compose_native("sc",     Interpreter::c_store_c);  // store
-    self.compose_native(".c",     Interpreter::c_store_c);  // store
-    self.compose_native("c",      Interpreter::c_push_c);   // retrieve
-    // math operations
-    self.compose_native("+",      Interpreter::c_add);      // add
-    self.compose_native("+_",     Interpreter::c_add_all);  // add all
-    self.compose_native("-",      Interpreter::c_sub);      // subtract
-    self.compose_native("x",      Interpreter::c_mult);     // multiply
-    self.compose_native("x_",     Interpreter::c_mult_all); // multiply all
-    self.compose_native("/",      Interpreter::c_div);      // divide
-    self.compose_native("chs",    Interpreter::c_chs);      // change sign
-    self.compose_native("abs",    Interpreter::c_abs);      // absolute value
-    self.compose_native("round",  Interpreter::c_round);    // round
-    self.compose_native("int",    Interpreter::c_round);
-    self.compose_native("inv",    Interpreter::c_inv);      // invert (1/x)
-    self.compose_native("sqrt",   Interpreter::c_sqrt);     // square root
-    self.compose_native("throot", Interpreter::c_throot);   // nth root
-    self.compose_native("proot",  Interpreter::c_proot);    // find principal roots
-    self.compose_native("^",      Interpreter::c_exp);      // exponentiation
-    self.compose_native("exp",    Interpreter::c_exp);
-    self.compose_native("%",      Interpreter::c_mod);      // modulus
-    self.compose_native("mod",    Interpreter::c_mod);
-    self.compose_native("!",      Interpreter::c_fact);     // factorial
-    self.compose_native("gcd",    Interpreter::c_gcd);      // greatest common divisor
-    self.compose_native("pi",     Interpreter::c_pi);       // pi
-    self.compose_native("e",      Interpreter::c_euler);    // Euler's constant
-    self.compose_native("d_r",    Interpreter::c_dtor);     // degrees to radians
-    self.compose_native("r_d",    Interpreter::c_rtod);     // radians to degrees
-    self.compose_native("sin",    Interpreter::c_sin);      // sine
-    self.compose_native("asin",   Interpreter::c_asin);     // arcsine
-    self.compose_native("cos",    Interpreter::c_cos);      // cosine
-    self.compose_native("acos",   Interpreter::c_acos);     // arccosine
-    self.compose_native("tan",    Interpreter::c_tan);      // tangent
-    self.compose_native("atan",   Interpreter::c_atan);     // arctangent
-    self.compose_native("log2",   Interpreter::c_log2);     // logarithm (base 2)
-    self.compose_native("log",    Interpreter::c_log10);    // logarithm (base 10)
-    self.compose_native("log10",  Interpreter::c_log10);
-    self.compose_native("logn",   Interpreter::c_logn);     // logarithm (base n)
-    self.compose_native("ln",     Interpreter::c_ln);       // natural logarithm
-    // control flow
-    self.compose_native("fn",     Interpreter::c_fn);       // function definition
-    self.compose_native("(",      Interpreter::c_comment);  // function definition
-  }
-
-  fn process_node(&mut self, op: &str) {
-    if self.cmap.contains_key(op) { // native comp command?
-      let f = self.cmap[op];
-      f(self, op);
-    } else {
-      let result: Option<usize> = self.is_user_function(op); // user-defined function?
-
-      match result {
-        Some(index) => { // user-defined function
-          // copy user function ops (fops) into main ops
-          for i in (0..self.fns[index].fops.len()).rev() {
-            let fop: String = self.fns[index].fops[i].clone();
-            self.ops.insert(0, fop);
-          }
-        }
-        None => { // neither native command nor user-defined function
-          // push value onto stack
-          self.stack.push(op.to_string());
-        }
-      }
-    }
+  fn parse_ref(&self, cmd: &clap::Command, arg: Option<&clap::Arg>, value: &OsStr) -> Result<String, clap::Error> {
+    StringValueParser::new().parse_ref(cmd, arg, value)
   }
 
-  // pop from stack helpers ----------------------------------------------------
-  fn pop_stack_f(&mut self) -> f64 {
-    let element: String = self.stack.pop().unwrap();
-    match self.parse_float(&element) {
-      Ok(val) => val, // parse success
-      Err(_error) => { // parse fail
-        eprintln!("{}: unknown expression [{}] is not a recognized operation \
-                   or value (f)", "error".bright_red(), element.cyan());
-        std::process::exit(99);
-      },
-    }
-  }
+  fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+    let cinter: Interpreter = Interpreter::with_config(Config::default()).ok()?;
+    let names: Vec<PossibleValue> = cinter.commands().into_iter().map(|name| PossibleValue::new(name.to_string())).collect();
 
-  fn pop_stack_u(&mut self) -> u64 {
-    let element: String = self.stack.pop().unwrap();
-    match self.parse_uint(&element) {
-      Ok(val) => val, // parse success
-      Err(_error) => { // parse fail
-        eprintln!("{}: unknown expression [{}] is not a recognized operation \
-                   or value (u)", "error".bright_red(), element.cyan());
-        std::process::exit(99);
-      },
-    }
+    Some(Box::new(names.into_iter()))
   }
+}
 
-  fn parse_float(&self, op: &String) -> Result<f64, ParseFloatError> {
-    let value: f64 = op.parse::<f64>()?;
-    Ok(value)
-  }
+// interpreter options (override configuration file defaults)
+#[derive(Args)]
+struct Options {
+  /// Restore and save session state using a file
+  #[arg(long, global = true, value_name = "FILE")]
+  state: Option<String>,
 
-  fn parse_uint(&self, op: &String) -> Result<u64, ParseIntError> {
-    let value: u64 = op.parse::<u64>()?;
-    Ok(value)
-  }
-  // ---------------------------------------------------------------------------
+  /// Number of decimal places displayed
+  #[arg(long, global = true, value_name = "N")]
+  precision: Option<usize>,
 
-  // confirm stack depth
-  fn check_stack_error(&self, min_depth: usize, command: &str) {
-    if self.stack.len() < min_depth {
-      eprintln!("{}: [{}] operation called without at least {min_depth} element(s) on stack", "error".bright_red(), command.to_string().cyan());
-      std::process::exit(99);
-    }
-  }
+  /// Number format (auto, fix, sci, eng, or si)
+  #[arg(long, global = true, value_name = "MODE")]
+  format: Option<Format>,
 
+  /// Display digits in groups of thousands (separated by SEP, ',' by default)
+  #[arg(long, global = true, value_name = "SEP", num_args = 0..=1, require_equals = true, default_missing_value = ",")]
+  group: Option<char>,
 
-  // command functions ---------------------------------------------------------
-  // ---- stack manipulation ---------------------------------------------------
+  /// Locale determining the decimal separator (e.g., de_DE)
+  #[arg(long, global = true, value_name = "LOCALE")]
+  locale: Option<String>,
 
-  fn c_drop(&mut self, op: &str) {
-    if !self.stack.is_empty() {
-      self.stack.pop();
-    } else {
-      println!("{}: [{}] operation called on empty stack", "warning".bright_yellow(), op.to_string().cyan());
-    }
-  }
+  /// Angle mode of trigonometric commands (deg or rad)
+  #[arg(long, global = true, value_name = "MODE")]
+  angle: Option<AngleMode>,
 
-  fn c_dup(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
+  /// Exact rational arithmetic (division of integers produces fractions)
+  #[arg(long, global = true)]
+  rational: bool,
 
-    let a: f64 = self.pop_stack_f();
+  /// Integer arithmetic (128-bit integers, with an error on overflow)
+  #[arg(long, global = true)]
+  integer: bool,
 
-    self.stack.push(a.to_string());
-    self.stack.push(a.to_string());
-  }
+  /// Strict mode (NaN or infinite results are errors)
+  #[arg(long, global = true)]
+  strict: bool,
 
-  fn c_swap(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 2, op);
+  /// Rounding mode of round and roundn (half-up, half-even, or toward-zero)
+  #[arg(long, global = true, value_name = "MODE")]
+  rounding: Option<Rounding>,
 
-    let end: usize = self.stack.len() - 1;
-    self.stack.swap(end, end-1);
-  }
+  /// Seed of the random number generator (reproducible rand results)
+  #[arg(long, global = true, value_name = "N")]
+  seed: Option<u64>,
 
-  fn c_cls(&mut self, _op: &str) {
-    self.stack.clear();
-  }
+  /// File of operations evaluated at startup
+  #[arg(long, global = true, value_name = "FILE")]
+  prelude: Option<PathBuf>,
 
-  fn c_roll(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
+  /// Maximum number of operations evaluated
+  #[arg(long = "max-ops", global = true, value_name = "N")]
+  max_ops: Option<usize>,
 
-    let o: String = self.stack.pop().unwrap(); // remove last
-    self.stack.splice(0..0, [o]);    // add as first
-  }
+  /// Maximum depth of user function calls
+  #[arg(long = "max-depth", global = true, value_name = "N")]
+  max_depth: Option<usize>,
 
-  fn c_rot(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
+  /// Colored output (auto, always, or never)
+  #[arg(long, global = true, value_name = "WHEN")]
+  color: Option<ColorMode>,
 
-    let o: String = self.stack.remove(0); // remove first
-    self.stack.push(o);                  // add as last
-  }
+  /// Disable colored output (same as --color=never)
+  #[arg(long = "no-color", global = true)]
+  no_color: bool,
 
+  /// Display only the top element of the stack (without color or indentation)
+  #[arg(long, global = true)]
+  top: bool,
 
-  // ---- memory usage ---------------------------------------------------------
+  /// Display each operation as it is evaluated along with the resulting stack
+  #[arg(long, global = true)]
+  trace: bool,
 
-  fn c_store_a(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    self.mem_a = self.pop_stack_f();
-  }
+  /// Check the operations (unknown operations and stack depth) without evaluating them
+  #[arg(long, global = true)]
+  check: bool,
+}
 
-  fn c_push_a(&mut self, _op: &str) {
-    self.stack.push(self.mem_a.to_string());
-  }
 
-  fn c_store_b(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
+fn main() {
+  // enable or disable backtrace on error
+  env::set_var("RUST_BACKTRACE", "0");
 
-    self.mem_b = self.pop_stack_f();
-  }
+  // parse command line arguments
+  let cli: Cli = Cli::parse();
+
+  // operations source (operations list, file, or interactive session)
+  enum Source {
+    List(Vec<String>),
+    Files(Vec<PathBuf>, Vec<String>),
+    Repl,
+    Debug(PathBuf),
+  }
+
+  let source: Source = match cli.command {
+    Some(CliCommand::Eval { ops }) => Source::List(ops),
+    Some(CliCommand::File { path }) => Source::Files(vec![path], Vec::new()),
+    Some(CliCommand::Repl) => Source::Repl,
+    Some(CliCommand::Debug { path }) => Source::Debug(path),
+    Some(CliCommand::Help { command }) => {
+      show_command_help(command.as_deref());
+      std::process::exit(0);
+    },
+    Some(CliCommand::Describe { op }) => {
+      match Interpreter::with_config(Config::default()) {
+        Ok(cinter) if show_describe(&cinter, &op) => std::process::exit(0),
+        Ok(_) => std::process::exit(99),
+        Err(error) => exit_with_error(error),
+      }
+    },
+    Some(CliCommand::Version) => {
+      show_version();
+      std::process::exit(0);
+    },
+    Some(CliCommand::Completions { shell }) => {
+      clap_complete::generate(shell, &mut Cli::command(), "comp", &mut std::io::stdout());
+      std::process::exit(0);
+    },
+    Some(CliCommand::Mona) => {
+      println!("{MONA}");
+      std::process::exit(0);
+    },
+    None if cli.help => {
+      show_help();
+      std::process::exit(0);
+    },
+    None if cli.version => {
+      show_version();
+      std::process::exit(0);
+    },
+    None if !cli.files.is_empty() => Source::Files(cli.files, cli.ops),
+    None => match cli.ops {
+      ops if !ops.is_empty() => Source::List(ops),
+      // if no operations are passed, read operations from piped input or
+      // display help
+      _ if std::io::stdin().is_terminal() => {
+        show_help();
+        std::process::exit(0);
+      },
+      _ => Source::List(vec!["-".to_string()]),
+    },
+  };
 
-  fn c_push_b(&mut self, _op: &str) {
-    self.stack.push(self.mem_b.to_string());
+  // load configuration file defaults and apply option overrides
+  let mut config: Config = match Config::load() {
+    Ok(config) => config,
+    Err(error) => exit_with_error(error),
+  };
+  let options: Options = cli.options;
+  if let Some(precision) = options.precision {
+    config.precision = Some(precision);
   }
-
-  fn c_store_c(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    self.mem_c = self.pop_stack_f();
+  if let Some(format) = options.format {
+    config.format = format;
   }
-
-  fn c_push_c(&mut self, _op: &str) {
-    self.stack.push(self.mem_c.to_string());
+  if let Some(group) = options.group {
+    config.group = Some(group);
   }
-
-
-  // ---- math operations ------------------------------------------------------
-
-  fn c_add(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 2, op);
-
-    let b: f64 = self.pop_stack_f();
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a + b).to_string());
+  if let Some(locale) = options.locale {
+    config.locale = Some(locale);
   }
-
-  fn c_add_all(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 2, op);
-
-    while self.stack.len() > 1 {
-      self.c_add(&op);
-    }
+  if let Some(angle) = options.angle {
+    config.angle = angle;
   }
-
-  fn c_sub(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 2, op);
-
-    let b: f64 = self.pop_stack_f();
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a - b).to_string());
+  if options.rational {
+    config.rational = true;
   }
-
-  fn c_mult(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 2, op);
-
-    let b: f64 = self.pop_stack_f();
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a * b).to_string());
+  if options.integer {
+    config.integer = true;
   }
-
-  fn c_mult_all(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 2, op);
-
-    while self.stack.len() > 1 {
-      self.c_mult(&op);
-    }
+  if options.strict {
+    config.strict = true;
   }
-
-  fn c_div(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 2, op);
-
-    let b: f64 = self.pop_stack_f();
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a / b).to_string());
+  if let Some(rounding) = options.rounding {
+    config.rounding = rounding;
   }
-
-  fn c_chs(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((-1.0 * a).to_string());
+  if let Some(seed) = options.seed {
+    config.seed = Some(seed);
   }
-
-  fn c_abs(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a.abs()).to_string());
+  if let Some(prelude) = options.prelude {
+    config.prelude = Some(prelude);
   }
-
-  fn c_round(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a.round()).to_string());
+  if let Some(max_ops) = options.max_ops {
+    config.max_ops = max_ops;
   }
-
-  fn c_inv(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((1.0 / a).to_string());
+  if let Some(max_depth) = options.max_depth {
+    config.max_depth = max_depth;
   }
-
-  fn c_sqrt(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a.sqrt()).to_string());
+  if let Some(color) = options.color {
+    config.color = color;
   }
-
-  fn c_throot(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 2, op);
-
-    let b: f64 = self.pop_stack_f();
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a.powf(1.0/b)).to_string());
+  if options.no_color {
+    config.color = ColorMode::Never;
   }
 
-  fn c_proot(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 3, op);
-
-    let c: f64 = self.pop_stack_f();
-    let b: f64 = self.pop_stack_f();
-    let a: f64 = self.pop_stack_f();
+  // enable or disable colored output (automatic mode disables color when
+  // output is not a terminal or the NO_COLOR environment variable is set)
+  let color: bool = match config.color {
+    ColorMode::Always => true,
+    ColorMode::Never => false,
+    ColorMode::Auto => {
+      let no_color: bool = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+      !no_color && std::io::stdout().is_terminal()
+    },
+  };
+  colored::control::set_override(color);
 
-    if (b*b - 4.0*a*c) < 0.0 {
-      self.stack.push((-1.0*b/(2.0*a)).to_string()); // root_1 real
-      self.stack.push(((4.0*a*c-b*b).sqrt()/(2.0*a)).to_string()); // root_1 imag
-      self.stack.push((-1.0*b/(2.0*a)).to_string()); // root_2 real
-      self.stack.push((-1.0*(4.0*a*c-b*b).sqrt()/(2.0*a)).to_string()); // root_2 imag
-    } else {
-      self.stack.push((-1.0*b+(b*b-4.0*a*c).sqrt()/(2.0*a)).to_string()); // root_1 real
-      self.stack.push(0.0.to_string()); // root_1 imag
-      self.stack.push((-1.0*b-(b*b-4.0*a*c).sqrt()/(2.0*a)).to_string()); // root_2 real
-      self.stack.push(0.0.to_string()); // root_2 imag
+  // construct command interpreter
+  let mut cinter: Interpreter = match Interpreter::with_config(config) {
+    Ok(cinter) => cinter,
+    Err(error) => exit_with_error(error),
+  };
+
+  // restore interpreter state from session file (saved again after evaluation)
+  if let Some(path) = &options.state {
+    if Path::new(path).exists() {
+      if let Err(error) = cinter.load_session(path) {
+        exit_with_error(error);
+      }
     }
   }
 
-  fn c_exp(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 2, op);
-
-    let b: f64 = self.pop_stack_f();
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a.powf(b)).to_string());
-  }
-
-  fn c_mod(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 2, op);
-
-    let b: f64 = self.pop_stack_f();
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a % b).to_string());
-  }
-
-  fn c_fact(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((Interpreter::factorial(a)).to_string());
-  }
-
-  fn c_gcd(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 2, op);
-
-    let b: u64 = self.pop_stack_u();
-    let a: u64 = self.pop_stack_u();
-
-    self.stack.push(Interpreter::gcd(a,b).to_string());
-  }
-
-  fn c_pi(&mut self, _op: &str) {
-    self.stack.push(std::f64::consts::PI.to_string());
-  }
-
-  fn c_euler(&mut self, _op: &str) {
-    self.stack.push(std::f64::consts::E.to_string());
-  }
-
-  fn c_dtor(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a.to_radians()).to_string());
-  }
-
-  fn c_rtod(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
+  let interactive: bool = matches!(source, Source::Repl | Source::Debug(_));
 
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a.to_degrees()).to_string());
-  }
-
-  fn c_sin(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a.sin()).to_string());
+  match source {
+    Source::List(ops) => {
+      // read operations list input from arguments (or standard input)
+      push_list(&mut cinter, &ops);
+    },
+    Source::Files(paths, ops) => {
+      // read operations list input from files followed by arguments
+      for path in &paths {
+        push_file(&mut cinter, path);
+      }
+      push_list(&mut cinter, &ops);
+    },
+    Source::Repl => {
+      // evaluate operations interactively
+      run_repl(&mut cinter, options.top, options.trace);
+    },
+    Source::Debug(path) => {
+      // step through file operations interactively
+      push_file(&mut cinter, &path);
+      run_debugger(&mut cinter);
+    },
+  }
+
+  // check operations list without evaluating it
+  if options.check {
+    let errors: Vec<comp::CompError> = cinter.check();
+    for error in &errors {
+      eprintln!("{}: {error}", "error".bright_red());
+    }
+    std::process::exit(if errors.is_empty() { 0 } else { 99 });
   }
 
-  fn c_asin(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a.asin()).to_string());
+  // process operations list
+  if let Err(error) = evaluate(&mut cinter, options.trace) {
+    exit_with_error(error);
   }
 
-  fn c_cos(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a.cos()).to_string());
+  // save interpreter state to session file
+  if let Some(path) = &options.state {
+    if let Err(error) = cinter.save_session(path) {
+      exit_with_error(error);
+    }
   }
 
-  fn c_acos(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a.acos()).to_string());
+  // display resulting computation stack (displayed after each line in an
+  // interactive session)
+  if !interactive {
+    show_stack(&cinter, options.top);
   }
 
-  fn c_tan(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
+  std::process::exit(0);
+}
 
-    self.stack.push((a.tan()).to_string());
+// process operations list (displaying printed lines after each operation, and
+// each operation and the resulting stack on standard error when tracing)
+fn evaluate(cinter: &mut Interpreter, trace: bool) -> Result<(), comp::CompError> {
+  loop {
+    let result: Result<Option<comp::Token>, comp::CompError> = cinter.step();
+    show_output(cinter);
+    match result? {
+      Some(token) if trace => eprintln!("  {:<12} {}", token.text.cyan(), stack_line(cinter)),
+      Some(_) => (),
+      None => return Ok(()),
+    }
   }
+}
 
-  fn c_atan(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a.atan()).to_string());
+// display lines printed by the print and pstack commands
+fn show_output(cinter: &mut Interpreter) {
+  for line in cinter.output.drain(..) {
+    println!("{line}");
   }
+}
 
-  fn c_log10(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a.log10()).to_string());
+// read operations list input from file
+fn push_file(cinter: &mut Interpreter, path: &Path) {
+  let display: String = path.display().to_string();
+  match fs::read_to_string(path) {
+    Ok(contents) => cinter.push_file_ops(&contents, &display),
+    Err(error) => {
+      exit_with_error(format!("could not read file [{}]: {error}", display.cyan()));
+    },
   }
+}
 
-  fn c_log2(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a.log2()).to_string());
+// read operations list input from arguments ('-' reads from standard input)
+fn push_list(cinter: &mut Interpreter, ops: &[String]) {
+  if ops.len() == 1 && ops[0] == "-" {
+    let mut input: String = String::new();
+    if let Err(error) = std::io::stdin().read_to_string(&mut input) {
+      exit_with_error(format!("could not read standard input: {error}"));
+    }
+    cinter.push_file_ops(&input, "<stdin>");
+  } else {
+    cinter.push_args(ops);
   }
+}
 
-  fn c_logn(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
+// computation stack on a single line (bottom to top)
+fn stack_line(cinter: &Interpreter) -> String {
+  let stack: Vec<String> = cinter.stack.iter().map(|element| cinter.format_value(element)).collect();
 
-    let b: f64 = self.pop_stack_f();
-    let a: f64 = self.pop_stack_f();
+  stack.join(" ")
+}
 
-    self.stack.push((a.log(b)).to_string());
+// display computation stack
+fn show_stack(cinter: &Interpreter, top: bool) {
+  if top {
+    if let Some(element) = cinter.stack.last() {
+      println!("{}", cinter.format_value(element));
+    }
+    return;
   }
 
-  fn c_ln(&mut self, op: &str) {
-    Interpreter::check_stack_error(self, 1, op);
-
-    let a: f64 = self.pop_stack_f();
-
-    self.stack.push((a.ln()).to_string());
+  for element in &cinter.stack {
+    println!("  {}", cinter.format_value(element).truecolor(0, 192, 255).bold());
   }
+}
 
+// interactive session (evaluate each line of input and display the stack)
+fn run_repl(cinter: &mut Interpreter, top: bool, trace: bool) {
+  let stdin = std::io::stdin();
+  let interactive: bool = stdin.is_terminal();
 
-  // -- control flow -----------------------------------------------------------
+  loop {
+    if interactive {
+      print!("{} ", ">".bright_black());
+      std::io::stdout().flush().ok();
+    }
 
-  fn c_fn(&mut self, _op: &str) {
-    // get function name
-    let fn_name: String = self.ops.remove(0);
+    let mut line: String = String::new();
+    match stdin.lock().read_line(&mut line) {
+      Ok(0) => break, // end of input
+      Ok(_) => (),
+      Err(error) => exit_with_error(format!("could not read standard input: {error}")),
+    }
 
-    // create new function instance and assign function name
-    self.fns.push(Function { name: fn_name,
-                             fops: Vec::new(),
-                           });
-    let fpos: usize = self.fns.len() - 1; // added function position in function vector
+    match line.trim() {
+      "" => continue,
+      "quit" | "exit" => break,
+      query if query.starts_with('?') => {
+        // describe command
+        show_describe(cinter, query[1..].trim());
+        continue;
+      },
+      _ => (),
+    }
 
-    // build out function operations my reading from interpreter ops
-    while self.ops[0] != "end" {
-      self.fns[fpos].fops.push(self.ops.remove(0));
+    cinter.push_file_ops(&line, "<repl>");
+    match evaluate(cinter, trace) {
+      Ok(()) => show_stack(cinter, top),
+      Err(error) => eprintln!("{}: {error}", "error".bright_red()),
     }
-    self.ops.remove(0); // remove "end" op
   }
+}
 
-  // is operator a user defined function?
-  fn is_user_function(&self, op: &str) -> Option<usize> {
-    if !self.fns.is_empty() {
-      for i in 0..self.fns.len() {
-        if self.fns[i].name == op {
-          return Some(i);
-        }
-      }
+// interactive debugger (evaluate pending operations one at a time, stopping
+// at breakpoints set on operations or user function names)
+fn run_debugger(cinter: &mut Interpreter) {
+  let stdin = std::io::stdin();
+  let interactive: bool = stdin.is_terminal();
+  let mut breakpoints: Vec<String> = Vec::new();
+
+  while let Some(next) = cinter.ops.front() {
+    println!("  {} {} ({})", "next".bright_black(), next.text.cyan(), next.span);
+    if interactive {
+      print!("{} ", "(debug)".bright_black());
+      std::io::stdout().flush().ok();
     }
-    None
-  }
 
-  fn c_comment(&mut self, _op: &str) {
-    let mut nested: usize = 0;
+    let mut line: String = String::new();
+    match stdin.lock().read_line(&mut line) {
+      Ok(0) => { // end of input (stop debugging)
+        cinter.ops.clear();
+        return;
+      },
+      Ok(_) => (),
+      Err(error) => exit_with_error(format!("could not read standard input: {error}")),
+    }
 
-    while !self.ops.is_empty() {
-      let op = self.ops.remove(0);
-      match &op[..] {
-        "(" => {
-          nested += 1;
-        },
-        ")" => {
-          if nested == 0 {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+      [] | ["step"] | ["s"] => {
+        if !debug_step(cinter) {
+          return;
+        }
+      },
+      ["continue"] | ["c"] => {
+        // evaluate at least one operation, then stop at the next breakpoint
+        loop {
+          if !debug_step(cinter) {
             return;
-          } else {
-            nested -= 1;
           }
-        },
-        _ => (),
-      }
+          match cinter.ops.front() {
+            Some(next) if breakpoints.contains(&next.text) => {
+              let kind: &str = if cinter.is_function(&next.text) { "function" } else { "operation" };
+              println!("  {} {kind} [{}]", "breakpoint".bright_yellow(), next.text.cyan());
+              break;
+            },
+            Some(_) => (),
+            None => break,
+          }
+        }
+      },
+      ["print", "stack"] | ["p", "stack"] => show_stack(cinter, false),
+      ["print", "registers"] | ["p", "registers"] => {
+        for (name, value) in cinter.registers() {
+          println!("  {name} = {}", cinter.format_value(value));
+        }
+        for (name, value) in cinter.variables() {
+          println!("  {name} = {}", cinter.format_value(value));
+        }
+      },
+      ["break"] | ["b"] => {
+        for op in &breakpoints {
+          println!("  {}", op.cyan());
+        }
+      },
+      ["break", ops @ ..] | ["b", ops @ ..] => {
+        for op in ops {
+          if !breakpoints.iter().any(|breakpoint| breakpoint == op) {
+            breakpoints.push(op.to_string());
+          }
+        }
+      },
+      ["delete", ops @ ..] | ["d", ops @ ..] => breakpoints.retain(|breakpoint| !ops.contains(&breakpoint.as_str())),
+      ["quit"] | ["q"] | ["exit"] => {
+        cinter.ops.clear();
+        return;
+      },
+      ["help"] | ["h"] => {
+        println!("  step (s)                 evaluate the next operation");
+        println!("  continue (c)             evaluate operations until a breakpoint is reached");
+        println!("  print stack (p stack)    display the stack");
+        println!("  print registers          display the memory registers and variables");
+        println!("  break (b) [<op> ...]     set breakpoints on operations or user functions (or list breakpoints)");
+        println!("  delete (d) <op> ...      remove breakpoints");
+        println!("  quit (q)                 stop debugging");
+      },
+      _ => eprintln!("{}: unknown debugger command [{}] (see help)", "error".bright_red(), line.trim().cyan()),
     }
   }
 
+  println!("  {}", "finished".bright_black());
+  show_stack(cinter, false);
+}
 
-  // support functions ---------------------------------------------------------
-
-  // factorial
-  fn factorial(o: f64) -> f64 {
-    let n = o.floor();
-
-    if n < 2.0 {
-      1.0
-    } else {
-      n * Interpreter::factorial(n - 1.0)
-    }
-  }
-
-  // greatest common divisor
-  fn gcd(a: u64, b: u64) -> u64 {
-    if b != 0 {
-      Interpreter::gcd(b, a % b)
-    } else {
-      a
-    }
+// evaluate the next operation in the debugger (returns false once evaluation
+// stops on error)
+fn debug_step(cinter: &mut Interpreter) -> bool {
+  let result: Result<Option<comp::Token>, comp::CompError> = cinter.step();
+  show_output(cinter);
+  match result {
+    Ok(Some(token)) => {
+      println!("  {:<12} {}", token.text.cyan(), stack_line(cinter));
+      true
+    },
+    Ok(None) => true,
+    Err(error) => {
+      eprintln!("{}: {error}", "error".bright_red());
+      false
+    },
   }
+}
 
+// display command description (arity, stack effect, and example)
+fn show_describe(cinter: &Interpreter, op: &str) -> bool {
+  let info = match cinter.describe(op) {
+    Some(info) => info,
+    None => {
+      eprintln!("{}: unknown command [{}]", "error".bright_red(), op.cyan());
+      return false;
+    },
+  };
+  let arity: String = match info.arity() {
+    Some(arity) => arity.to_string(),
+    None => "variable".to_string(),
+  };
+
+  println!("  {} - {}", op.bold(), info.description);
+  println!("    arity:    {arity}");
+  println!("    effect:   {}", info.effect);
+  println!("    example:  {}", info.example);
+
+  true
 }
 
+// report error and exit
+fn exit_with_error(error: impl std::fmt::Display) -> ! {
+  eprintln!("{}: {error}", "error".bright_red());
+  std::process::exit(99);
+}
 
 fn show_help() {
   println!();
@@ -729,19 +655,57 @@ fn show_help() {
   println!("    comp - command interpreter");
   println!();
   println!("{}", "USAGE".to_string().bold());
-  println!("    comp [version] [help]");
-  println!("    comp <list>");
-  println!("    comp -f <file>");
+  println!("    comp [version] [help [<command>]]");
+  println!("    comp describe <command>");
+  println!("    comp completions <shell>");
+  println!("    comp [options] <list>");
+  println!("    comp [options] -f <file> [-f <file> ...] [<list>]");
+  println!("    comp [options] -");
+  println!("    comp [options] eval <list>");
+  println!("    comp [options] file <file>");
+  println!("    comp [options] repl");
+  println!("    comp [options] debug -f <file>");
+  println!();
+  println!("{}", "SUBCOMMANDS".to_string().bold());
+  println!("    eval               evaluate an operations list");
+  println!("    file               evaluate the operations in a file");
+  println!("    repl               start an interactive session");
+  println!("    debug              step through the operations in a file interactively");
+  println!("    help               display help (for a subcommand)");
+  println!("    describe           describe a command (arity, stack effect, and example)");
+  println!("    version            show version");
+  println!("    completions        generate a shell completion script");
   println!();
   println!("{}", "OPTIONS".to_string().bold());
   println!("        --version      show version");
-  println!("    -f, --file         used to specify a path to a file");
+  println!("    -f, --file         used to specify a path to a file (repeatable)");
+  println!("    -                  read operations from standard input");
+  println!("        --state        restore and save session state using a file");
+  println!("        --precision    number of decimal places displayed");
+  println!("        --format       number format (auto, fix, sci, eng, or si)");
+  println!("        --group        display digits in groups of thousands (--group=SEP)");
+  println!("        --locale       locale determining the decimal separator (e.g., de_DE)");
+  println!("        --angle        angle mode of trigonometric commands (deg or rad)");
+  println!("        --rational     exact rational arithmetic");
+  println!("        --integer      128-bit integer arithmetic (error on overflow)");
+  println!("        --strict       treat NaN or infinite results as errors");
+  println!("        --rounding     rounding mode (half-up, half-even, or toward-zero)");
+  println!("        --seed         seed of the random number generator");
+  println!("        --prelude      file of operations evaluated at startup");
+  println!("        --max-ops      maximum number of operations evaluated");
+  println!("        --max-depth    maximum depth of user function calls");
+  println!("        --color        colored output (auto, always, or never)");
+  println!("        --no-color     disable colored output");
+  println!("        --top          display only the top element of the stack");
+  println!("        --trace        display each operation and the resulting stack");
+  println!("        --check        check the operations without evaluating them");
   println!("        --help         display help and usage information");
   println!();
   println!("{}", "DESCRIPTION".to_string().bold());
   println!("The interpreter takes a sequence of (postfix) operations \
   <list> as command line arguments or a file argument <file> that specifies \
-  the path to a file containing a list of operations. Each operation is \
+  the path to a file containing a list of operations. Operations are read \
+  from standard input when '-' is given or when input is piped. Each operation is \
   either a command (symbol) or a value. As examples, 'comp 3 4 +' adds \
   the values 3 and 4 and '3 dup x 4 dup x +' computes the sum of the \
   squares of 3 and 4. The available commands are listed below.");
@@ -759,6 +723,22 @@ fn show_help() {
   println!();
 }
 
+// display help for a subcommand (or general help)
+fn show_command_help(command: Option<&str>) {
+  let mut cli = Cli::command();
+  cli.build();
+
+  match command.and_then(|name| cli.find_subcommand_mut(name)) {
+    Some(subcommand) => {
+      subcommand.print_long_help().ok();
+    },
+    None => match command {
+      Some(name) => exit_with_error(format!("unknown subcommand [{}]", name.cyan())),
+      None => show_help(),
+    },
+  }
+}
+
 fn show_version() {
   let version: &str = env!("CARGO_PKG_VERSION");
   println!("  comp {}", version.to_string() + RELEASE_STATUS);
@@ -812,8 +792,3 @@ const MONA: &str = "!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!>''''''<!!!!!!!!!!!!!!
        !!!!!!!!>        c$$$$$$$$$$$$$$$$$$$$$$$$$$$$$$$$$$$$.\n\
        !!!!!!''       ,d$$$$$$$$$$$$$$$$$$$$$$$$$$$$$$$$$$$$$      allen mullen";
 
-
-
-#[cfg(test)]
-#[path = "./comp_test.rs"]
-mod comp_test;
@@ -1,54 +1,59 @@
+use crate::AngleMode;
+use crate::CompError;
+use crate::Config;
 use crate::Interpreter;
+use crate::Rounding;
+use crate::Span;
+use crate::Value;
 
 #[cfg(test)]
-
 mod comp_tests {
 
   #[test]
   fn test_core() {
-    let mut test_cinter = super::Interpreter::new();
-
-    test_cinter.stack.push(1.0.to_string());
-    test_cinter.stack.push(2.0.to_string());
-    test_cinter.stack.push(3.0.to_string());
-    test_cinter.stack.push(4.0.to_string());
-
-    test_cinter.c_rot("o");
-    test_cinter.c_rot("o");
-    test_cinter.c_roll("o");
-    test_cinter.c_roll("o");
-
-    test_cinter.c_dtor("o");
-    test_cinter.c_cos("o");
-    test_cinter.c_acos("o");
-    test_cinter.c_sin("o");
-    test_cinter.c_asin("o");
-    test_cinter.c_tan("o");
-    test_cinter.c_atan("o");
-    test_cinter.c_rtod("o");
-    test_cinter.c_round("o");
-    test_cinter.c_roll("o");
-    test_cinter.c_roll("o");
-    test_cinter.c_roll("o");
-    test_cinter.c_roll("o");
-    test_cinter.c_dup("o");
-    test_cinter.c_drop("o");
-    test_cinter.c_swap("o");
-    test_cinter.c_swap("o");
-    test_cinter.c_add("o");
-    test_cinter.c_sub("o");
-    test_cinter.c_div("o");
-
-    test_cinter.stack.push(10.0.to_string());
-    test_cinter.c_log2("o");
-    test_cinter.stack.push(10.0.to_string());
-    test_cinter.stack.push(2.0.to_string());
-    test_cinter.c_logn("o");
-    test_cinter.c_sub("o");
-    test_cinter.c_round("o");
-    test_cinter.c_add("o");
-
-    assert!(test_cinter.pop_stack_f() == -0.2);
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.stack.push(super::Value::Float(1.0));
+    test_cinter.stack.push(super::Value::Float(2.0));
+    test_cinter.stack.push(super::Value::Float(3.0));
+    test_cinter.stack.push(super::Value::Float(4.0));
+
+    test_cinter.c_rot("o").unwrap();
+    test_cinter.c_rot("o").unwrap();
+    test_cinter.c_roll("o").unwrap();
+    test_cinter.c_roll("o").unwrap();
+
+    test_cinter.c_dtor("o").unwrap();
+    test_cinter.c_cos("o").unwrap();
+    test_cinter.c_acos("o").unwrap();
+    test_cinter.c_sin("o").unwrap();
+    test_cinter.c_asin("o").unwrap();
+    test_cinter.c_tan("o").unwrap();
+    test_cinter.c_atan("o").unwrap();
+    test_cinter.c_rtod("o").unwrap();
+    test_cinter.c_round("o").unwrap();
+    test_cinter.c_roll("o").unwrap();
+    test_cinter.c_roll("o").unwrap();
+    test_cinter.c_roll("o").unwrap();
+    test_cinter.c_roll("o").unwrap();
+    test_cinter.c_dup("o").unwrap();
+    test_cinter.c_drop("o").unwrap();
+    test_cinter.c_swap("o").unwrap();
+    test_cinter.c_swap("o").unwrap();
+    test_cinter.c_add("o").unwrap();
+    test_cinter.c_sub("o").unwrap();
+    test_cinter.c_div("o").unwrap();
+
+    test_cinter.stack.push(super::Value::Float(10.0));
+    test_cinter.c_log2("o").unwrap();
+    test_cinter.stack.push(super::Value::Float(10.0));
+    test_cinter.stack.push(super::Value::Float(2.0));
+    test_cinter.c_logn("o").unwrap();
+    test_cinter.c_sub("o").unwrap();
+    test_cinter.c_round("o").unwrap();
+    test_cinter.c_add("o").unwrap();
+
+    assert!(test_cinter.pop_stack_f().unwrap() == -0.2);
   }
 
   #[test]
@@ -59,118 +64,1708 @@ mod comp_tests {
 
   #[test]
   fn test_roots() {
-    let mut test_cinter = super::Interpreter::new();
-
-    test_cinter.stack.push(2.0.to_string());
-    test_cinter.c_dup("o");
-    test_cinter.c_sqrt("o");
-    test_cinter.c_swap("o");
-    test_cinter.stack.push(32.0.to_string());
-    test_cinter.c_exp("o");
-    test_cinter.stack.push((32.0 * 2.0).to_string());
-    test_cinter.c_throot("o");
-
-    assert!(test_cinter.pop_stack_f() == test_cinter.pop_stack_f());
-
-    test_cinter.stack.push(1.0.to_string());
-    test_cinter.stack.push((-2.0).to_string());
-    test_cinter.c_chs("o");
-    test_cinter.c_chs("o");
-    test_cinter.c_pi("o");
-    test_cinter.c_mult("o");
-    test_cinter.c_pi("o");
-    test_cinter.stack.push(2.0.to_string());
-    test_cinter.c_exp("o");
-    test_cinter.stack.push(1.0.to_string());
-    test_cinter.c_add("o");
-    test_cinter.c_proot("o");
-    test_cinter.c_add_all("o");
-    test_cinter.stack.push(2.0.to_string());
-    test_cinter.c_div("o");
-    test_cinter.c_pi("o");
-
-    assert!(test_cinter.pop_stack_f() == test_cinter.pop_stack_f());
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.stack.push(super::Value::Float(2.0));
+    test_cinter.c_dup("o").unwrap();
+    test_cinter.c_sqrt("o").unwrap();
+    test_cinter.c_swap("o").unwrap();
+    test_cinter.stack.push(super::Value::Float(32.0));
+    test_cinter.c_exp("o").unwrap();
+    test_cinter.stack.push(super::Value::Float(32.0 * 2.0));
+    test_cinter.c_throot("o").unwrap();
+
+    assert!(test_cinter.pop_stack_f().unwrap() == test_cinter.pop_stack_f().unwrap());
+
+    test_cinter.stack.push(super::Value::Float(1.0));
+    test_cinter.stack.push(super::Value::Float(-2.0));
+    test_cinter.c_chs("o").unwrap();
+    test_cinter.c_chs("o").unwrap();
+    test_cinter.c_pi("o").unwrap();
+    test_cinter.c_mult("o").unwrap();
+    test_cinter.c_pi("o").unwrap();
+    test_cinter.stack.push(super::Value::Float(2.0));
+    test_cinter.c_exp("o").unwrap();
+    test_cinter.stack.push(super::Value::Float(1.0));
+    test_cinter.c_add("o").unwrap();
+    test_cinter.c_proot("o").unwrap();
+    test_cinter.c_add_all("o").unwrap();
+    test_cinter.stack.push(super::Value::Float(2.0));
+    test_cinter.c_div("o").unwrap();
+    test_cinter.c_pi("o").unwrap();
+
+    assert!(test_cinter.pop_stack_f().unwrap() == test_cinter.pop_stack_f().unwrap());
   }
 
   #[test]
   #[should_panic]
   fn test_cls() {
-    let mut test_cinter = super::Interpreter::new();
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
 
-    test_cinter.stack.push(1.0.to_string());
-    test_cinter.stack.push(2.0.to_string());
-    test_cinter.stack.push(3.0.to_string());
-    test_cinter.stack.push(4.0.to_string());
-    test_cinter.stack.push(1.0.to_string());
-    test_cinter.stack.push(2.0.to_string());
-    test_cinter.stack.push(3.0.to_string());
-    test_cinter.stack.push(4.0.to_string());
-    test_cinter.stack.push(1.0.to_string());
-    test_cinter.stack.push(2.0.to_string());
-    test_cinter.stack.push(3.0.to_string());
-    test_cinter.stack.push(4.0.to_string());
-    test_cinter.c_cls("o");
+    test_cinter.stack.push(super::Value::Float(1.0));
+    test_cinter.stack.push(super::Value::Float(2.0));
+    test_cinter.stack.push(super::Value::Float(3.0));
+    test_cinter.stack.push(super::Value::Float(4.0));
+    test_cinter.stack.push(super::Value::Float(1.0));
+    test_cinter.stack.push(super::Value::Float(2.0));
+    test_cinter.stack.push(super::Value::Float(3.0));
+    test_cinter.stack.push(super::Value::Float(4.0));
+    test_cinter.stack.push(super::Value::Float(1.0));
+    test_cinter.stack.push(super::Value::Float(2.0));
+    test_cinter.stack.push(super::Value::Float(3.0));
+    test_cinter.stack.push(super::Value::Float(4.0));
+    test_cinter.c_cls("o").unwrap();
 
-    assert!(test_cinter.pop_stack_f() == 0.0);
+    assert!(test_cinter.pop_stack_f().unwrap() == 0.0);
   }
 
   #[test]
   fn test_mem() {
-    let mut test_cinter = super::Interpreter::new();
-
-    test_cinter.stack.push(1.0.to_string());
-    test_cinter.stack.push(2.0.to_string());
-    test_cinter.stack.push(3.0.to_string());
-    test_cinter.stack.push(4.0.to_string());
-    test_cinter.stack.push(1.0.to_string());
-    test_cinter.stack.push(2.0.to_string());
-    test_cinter.stack.push(3.0.to_string());
-    test_cinter.stack.push(4.0.to_string());
-    test_cinter.stack.push(1.0.to_string());
-    test_cinter.stack.push(2.0.to_string());
-    test_cinter.stack.push(3.0.to_string());
-    test_cinter.stack.push(4.0.to_string());
-    test_cinter.c_chs("o");
-    test_cinter.c_abs("o");
-    test_cinter.c_inv("o");
-    test_cinter.c_inv("o");
-    test_cinter.c_pi("o");
-    test_cinter.c_euler("o");
-    test_cinter.stack.push(0.0.to_string());
-    test_cinter.c_store_b("o"); // 0
-    test_cinter.c_store_a("o"); // e
-    test_cinter.c_store_c("o"); // pi
-    test_cinter.c_cls("o");
-    test_cinter.c_push_b("o"); // 0
-    test_cinter.c_push_c("o"); // pi
-    test_cinter.c_add("o");
-    test_cinter.c_push_a("o"); // e
-    test_cinter.c_add("o");
-
-    assert!(test_cinter.pop_stack_f() == std::f64::consts::PI + std::f64::consts::E);
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.stack.push(super::Value::Float(1.0));
+    test_cinter.stack.push(super::Value::Float(2.0));
+    test_cinter.stack.push(super::Value::Float(3.0));
+    test_cinter.stack.push(super::Value::Float(4.0));
+    test_cinter.stack.push(super::Value::Float(1.0));
+    test_cinter.stack.push(super::Value::Float(2.0));
+    test_cinter.stack.push(super::Value::Float(3.0));
+    test_cinter.stack.push(super::Value::Float(4.0));
+    test_cinter.stack.push(super::Value::Float(1.0));
+    test_cinter.stack.push(super::Value::Float(2.0));
+    test_cinter.stack.push(super::Value::Float(3.0));
+    test_cinter.stack.push(super::Value::Float(4.0));
+    test_cinter.c_chs("o").unwrap();
+    test_cinter.c_abs("o").unwrap();
+    test_cinter.c_inv("o").unwrap();
+    test_cinter.c_inv("o").unwrap();
+    test_cinter.c_pi("o").unwrap();
+    test_cinter.c_euler("o").unwrap();
+    test_cinter.stack.push(super::Value::Float(0.0));
+    test_cinter.c_store_b("o").unwrap(); // 0
+    test_cinter.c_store_a("o").unwrap(); // e
+    test_cinter.c_store_c("o").unwrap(); // pi
+    test_cinter.c_cls("o").unwrap();
+    test_cinter.c_push_b("o").unwrap(); // 0
+    test_cinter.c_push_c("o").unwrap(); // pi
+    test_cinter.c_add("o").unwrap();
+    test_cinter.c_push_a("o").unwrap(); // e
+    test_cinter.c_add("o").unwrap();
+
+    assert!(test_cinter.pop_stack_f().unwrap() == std::f64::consts::PI + std::f64::consts::E);
   }
 
   #[test]
   fn test_cmp() {
-    let mut test_cinter = super::Interpreter::new();
-
-    test_cinter.stack.push(10.0.to_string());
-    test_cinter.c_log10("o");
-    test_cinter.c_euler("o");
-    test_cinter.c_ln("o");
-    test_cinter.stack.push(105.0.to_string());
-    test_cinter.stack.push(2.0.to_string());
-    test_cinter.c_mod("o");
-    test_cinter.stack.push(3049.0.to_string());
-    test_cinter.stack.push(1009.0.to_string());
-    test_cinter.c_gcd("o");
-    test_cinter.c_mult_all("o");
-
-    assert!(test_cinter.pop_stack_f() == 1.0);
-
-    test_cinter.stack.push(20.0.to_string());
-    test_cinter.c_fact("o");
-
-    assert!(test_cinter.pop_stack_f() == 2432902008176640000.0);
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.stack.push(super::Value::Float(10.0));
+    test_cinter.c_log10("o").unwrap();
+    test_cinter.c_euler("o").unwrap();
+    test_cinter.c_ln("o").unwrap();
+    test_cinter.stack.push(super::Value::Float(105.0));
+    test_cinter.stack.push(super::Value::Float(2.0));
+    test_cinter.c_mod("o").unwrap();
+    test_cinter.stack.push(super::Value::Float(3049.0));
+    test_cinter.stack.push(super::Value::Float(1009.0));
+    test_cinter.c_gcd("o").unwrap();
+    test_cinter.c_mult_all("o").unwrap();
+
+    assert!(test_cinter.pop_stack_f().unwrap() == 1.0);
+
+    test_cinter.stack.push(super::Value::Float(20.0));
+    test_cinter.c_fact("o").unwrap();
+
+    assert!(test_cinter.pop_stack_f().unwrap() == 2432902008176640000.0);
+  }
+
+  #[test]
+  fn test_value() {
+    assert!(super::Value::parse("42") == Some(super::Value::Int(42)));
+    assert!(super::Value::parse("1e5") == Some(super::Value::Float(100000.0)));
+    assert!(super::Value::parse("eggs").is_none());
+    assert!(super::Value::Float(3.0).to_string() == "3");
+  }
+
+  #[test]
+  fn test_errors() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.stack.push(super::Value::Float(1.0));
+
+    assert!(test_cinter.c_add("+") == Err(super::CompError::StackUnderflow { op: "+".to_string(), depth: 2 }));
+
+    test_cinter.push_ops("2\n  eggs +");
+
+    assert!(test_cinter.process_ops() == Err(super::CompError::Located {
+      span: super::Span { index: 2, source: None, line: Some(2) },
+      error: Box::new(super::CompError::UnknownExpression("eggs".to_string())),
+    }));
+    assert!(test_cinter.ops.is_empty());
+
+    test_cinter.push_ops("fn square dup x");
+
+    assert!(test_cinter.process_ops().unwrap_err().to_string() == "function definition [square] is missing a terminating [end] (op 1, line 1)");
+
+    // text values are not numbers
+    for op in ["sqrt", "ln", "re", "im", "conj", "arg", "mag", "isnan", "isinf", "isfinite", "0.1 tofrac"] {
+      test_cinter.push_ops(&format!("cls 1994 roman {op}"));
+      assert!(matches!(test_cinter.process_ops(), Err(super::CompError::Located { error, .. })
+        if *error == super::CompError::NotANumber("MCMXCIV".to_string())));
+    }
+  }
+
+  #[test]
+  fn test_session() {
+    let path: String = std::env::temp_dir().join("comp_test_session.json").to_string_lossy().to_string();
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops(&format!("fn square dup x end 3 sa 2 store k 4 save {path}"));
+    test_cinter.process_ops().unwrap();
+
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops(&format!("load {path} square a + recall k +"));
+    test_cinter.process_ops().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(test_cinter.pop_stack_f().unwrap() == 21.0);
+  }
+
+  #[test]
+  fn test_config() {
+    let config: super::Config = super::Config::parse("precision = 4\nangle = \"deg\"").unwrap();
+    let mut test_cinter = super::Interpreter::with_config(config).unwrap();
+
+    test_cinter.stack.push(super::Value::Float(30.0));
+    test_cinter.c_sin("o").unwrap();
+
+    assert!(test_cinter.pop_stack_f().unwrap() == 0.49999999999999994);
+
+    test_cinter.stack.push(super::Value::Float(2.0));
+    test_cinter.c_sqrt("o").unwrap();
+
+    assert!(test_cinter.format_value(&test_cinter.stack[0]) == "1.4142");
+    assert!(super::Config::parse("colour = false").is_err());
+
+    // grouped values must be readable
+    assert!(super::Config::parse("group = \".\"").is_err());
+    assert!(super::Config::parse("group = \".\"\nlocale = \"de_DE\"").is_ok());
+    for group in [',', '5', '-', '+'] {
+      let config: super::Config = super::Config { group: Some(group), locale: Some("de_DE".to_string()), ..super::Config::default() };
+      assert!(matches!(super::Interpreter::with_config(config), Err(super::CompError::InvalidConfig(_))));
+    }
+  }
+
+  #[test]
+  fn test_step_limit() {
+    let config: super::Config = super::Config { max_ops: 1000, ..super::Config::default() };
+    let mut test_cinter = super::Interpreter::with_config(config).unwrap();
+
+    test_cinter.push_ops("fn loop loop end loop");
+
+    assert!(test_cinter.process_ops().unwrap_err().to_string() == "evaluation exceeded the maximum of 1000 operations (op 3, line 1)");
+
+    test_cinter.push_ops("1 2 +");
+    test_cinter.process_ops().unwrap();
+
+    assert!(test_cinter.pop_stack_f().unwrap() == 3.0);
+  }
+
+  #[test]
+  fn test_describe() {
+    let test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    assert!(test_cinter.describe("+").unwrap().arity() == Some(2));
+    assert!(test_cinter.describe("+_").unwrap().arity().is_none());
+    assert!(test_cinter.describe("pi").unwrap().arity() == Some(0));
+    assert!(test_cinter.describe("eggs").is_none());
+
+    // every command example evaluates to the documented result
+    for name in test_cinter.commands() {
+      let example: &str = test_cinter.describe(name).unwrap().example;
+      if let Some((ops, result)) = example.split_once("=>") {
+        let mut example_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+        example_cinter.push_ops(ops);
+        example_cinter.process_ops().unwrap();
+
+        let stack: Vec<String> = example_cinter.stack.iter().map(|element| example_cinter.format_value(element)).collect();
+        assert!(stack.join(" ") == result.trim(), "example of [{name}] evaluates to [{}]", stack.join(" "));
+      }
+    }
+  }
+
+  #[test]
+  fn test_step() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("fn sq dup x end 3 sq");
+    assert!(test_cinter.step().unwrap().unwrap().text == "fn");
+    assert!(test_cinter.step().unwrap().unwrap().text == "3");
+    assert!(test_cinter.step().unwrap().unwrap().text == "sq");
+    assert!(test_cinter.step().unwrap().unwrap().text == "dup");
+    assert!(test_cinter.stack.len() == 2);
+    assert!(test_cinter.step().unwrap().unwrap().text == "x");
+    assert!(test_cinter.step().unwrap().is_none());
+    assert!(test_cinter.pop_stack_f().unwrap() == 9.0);
+
+    test_cinter.push_ops("3 sa");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.registers()[0] == ("a", &super::Value::Int(3)));
+    assert!(test_cinter.is_function("sq"));
+    assert!(!test_cinter.is_function("dup"));
+
+    // errors clear the pending operations
+    test_cinter.push_ops("+ 1");
+    assert!(test_cinter.step().is_err());
+    assert!(test_cinter.step().unwrap().is_none());
+  }
+
+  #[test]
+  fn test_check() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("fn sq dup x end ( comment ) 3 sq 4 sq + 1 2 3 +_ 2 +");
+    assert!(test_cinter.check().is_empty());
+    assert!(test_cinter.stack.is_empty()); // nothing evaluated
+
+    test_cinter.ops.clear();
+    test_cinter.push_ops("fn sq dup x end sq 1 + eggs load");
+    let errors: Vec<super::CompError> = test_cinter.check();
+    assert!(errors.len() == 3);
+    assert!(errors[0] == super::CompError::StackUnderflow { op: "dup".to_string(), depth: 1 }
+      .at(&super::Span { index: 3, source: None, line: Some(1) }));
+    assert!(errors[1] == super::CompError::UnknownExpression("eggs".to_string())
+      .at(&super::Span { index: 9, source: None, line: Some(1) }));
+    assert!(errors[2] == super::CompError::MissingArgument("load".to_string())
+      .at(&super::Span { index: 10, source: None, line: Some(1) }));
+
+    test_cinter.ops.clear();
+    test_cinter.push_ops("fn sq dup x end 1 2 3 map sq fold - filter isprime zipwith eggs reduce");
+    let errors: Vec<super::CompError> = test_cinter.check();
+    assert!(errors.len() == 2);
+    assert!(errors[0] == super::CompError::UnknownFunction("eggs".to_string())
+      .at(&super::Span { index: 16, source: None, line: Some(1) }));
+    assert!(errors[1] == super::CompError::MissingArgument("reduce".to_string())
+      .at(&super::Span { index: 17, source: None, line: Some(1) }));
+
+    test_cinter.ops.clear();
+    test_cinter.push_ops("fn sq dup x");
+    assert!(test_cinter.check() == vec![super::CompError::IncompleteFunction("sq".to_string())
+      .at(&super::Span { index: 1, source: None, line: Some(1) })]);
+  }
+
+  #[test]
+  fn test_bigint() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("100 !");
+    test_cinter.process_ops().unwrap();
+    let fact: String = test_cinter.stack.pop().unwrap().to_string();
+    assert!(fact.len() == 158);
+    assert!(fact.starts_with("933262154439441526816992388562667004907"));
+
+    test_cinter.push_ops("2 64 ^ 1 - 2 64 ^ 2 32 ^ gcd 2 0.5 x");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[0].to_string() == "18446744073709551615");
+    assert!(test_cinter.stack[1] == super::Value::Int(4294967296));
+    assert!(test_cinter.stack[2] == super::Value::Float(1.0));
+
+    // integer results are stored as i64 values when they fit
+    test_cinter.push_ops("cls 99999999999999999999 99999999999999999998 -");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == vec![super::Value::Int(1)]);
+  }
+
+  #[test]
+  fn test_rational() {
+    let config: super::Config = super::Config { rational: true, ..super::Config::default() };
+    let mut test_cinter = super::Interpreter::with_config(config).unwrap();
+
+    test_cinter.push_ops("1 3 / 1 6 / + 2 -2 ^ 1 3 / 3 x 2 3 / tofloat");
+    test_cinter.process_ops().unwrap();
+    let stack: Vec<String> = test_cinter.stack.iter().map(|element| element.to_string()).collect();
+    assert!(stack == vec!["1/2", "1/4", "1", "0.6666666666666666"]);
+    assert!(test_cinter.stack[2] == super::Value::Int(1));
+
+    // fractions are only produced from integers in rational mode
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1 4 / 1/4 1/4 +");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[0] == super::Value::Float(0.25));
+    assert!(test_cinter.stack[1].to_string() == "1/2");
+  }
+
+  #[test]
+  fn test_radix() {
+    assert!(super::Value::parse("0xff") == Some(super::Value::Int(255)));
+    assert!(super::Value::parse("0b1010") == Some(super::Value::Int(10)));
+    assert!(super::Value::parse("-0o755") == Some(super::Value::Int(-493)));
+    assert!(super::Value::parse("0x1_0").is_none());
+    assert!(super::Value::parse("0b12").is_none());
+    assert!(super::Value::parse("0x").is_none());
+    assert!(super::Value::parse("0xffffffffffffffffffff").unwrap().to_string() == "1208925819614629174706175");
+  }
+
+  #[test]
+  fn test_si() {
+    assert!(super::Value::parse("4.7k") == Some(super::Value::Float(4700.0)));
+    assert!(super::Value::parse("2M") == Some(super::Value::Int(2000000)));
+    assert!(super::Value::parse("100m") == Some(super::Value::Float(0.1)));
+    assert!(super::Value::parse("-22n") == Some(super::Value::Float(-22e-9)));
+    assert!(super::Value::parse("10u") == super::Value::parse("10µ"));
+    assert!(super::Value::parse("1e3k").is_none());
+    assert!(super::Value::parse("k").is_none());
+  }
+
+  #[test]
+  fn test_complex() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    assert!(super::Value::parse("3+4i") == Some(super::Value::Complex(num_complex::Complex64::new(3.0, 4.0))));
+    assert!(super::Value::parse("-2.5e-1-1e1i").unwrap().to_string() == "-0.25-10i");
+    assert!(super::Value::parse("4i").unwrap().to_string() == "4i");
+    assert!(super::Value::parse("3+i").is_none());
+
+    test_cinter.push_ops("3+4i 1-2i x 3+4i 3-4i x 1i 2 ^ 3+4i mag -4+3i conj");
+    test_cinter.process_ops().unwrap();
+    let stack: Vec<String> = test_cinter.stack.iter().map(|element| element.to_string()).collect();
+    assert!(stack == vec!["11-2i", "25", "-1", "5", "-4-3i"]);
+    assert!(test_cinter.stack[1] == super::Value::Float(25.0));
+
+    test_cinter.push_ops("cls -3+4i sqrt -1i ln -4 sqrt");
+    test_cinter.process_ops().unwrap();
+    let root: num_complex::Complex64 = test_cinter.stack[0].to_complex();
+    assert!((root.re - 1.0).abs() < 1e-12 && (root.im - 2.0).abs() < 1e-12);
+    assert!(test_cinter.stack[1] == super::Value::Complex(num_complex::Complex64::new(0.0, -std::f64::consts::FRAC_PI_2)));
+    assert!(test_cinter.stack[2] == super::Value::Complex(num_complex::Complex64::new(0.0, 2.0)));
+  }
+
+  #[test]
+  fn test_base() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("255 -10 2 64 ^ 0.5 hex");
+    test_cinter.process_ops().unwrap();
+    let stack: Vec<String> = test_cinter.stack.iter().map(|element| test_cinter.format_value(element)).collect();
+    assert!(stack == vec!["0xff", "-0xa", "0x10000000000000000", "0.5"]);
+
+    test_cinter.push_ops("bin");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.format_value(&test_cinter.stack[1]) == "-0b1010");
+
+    test_cinter.push_ops("dec");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.format_value(&test_cinter.stack[0]) == "255");
+  }
+
+  #[test]
+  fn test_format() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1230000 1e20 1e-9 0.5");
+    test_cinter.process_ops().unwrap();
+    let display = |cinter: &super::Interpreter| -> Vec<String> {
+      cinter.stack.iter().map(|element| cinter.format_value(element)).collect()
+    };
+    assert!(display(&test_cinter) == vec!["1230000", "1e20", "1e-9", "0.5"]);
+
+    test_cinter.push_ops("sci");
+    test_cinter.process_ops().unwrap();
+    assert!(display(&test_cinter) == vec!["1.23e6", "1e20", "1e-9", "5e-1"]);
+
+    test_cinter.push_ops("fix");
+    test_cinter.process_ops().unwrap();
+    test_cinter.config.precision = Some(2);
+    assert!(display(&test_cinter) == vec!["1230000", "100000000000000000000.00", "0.00", "0.50"]);
+
+    test_cinter.push_ops("auto 3 prec");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.config.precision == Some(3));
+    assert!(display(&test_cinter) == vec!["1230000", "1e20", "1e-9", "0.5"]);
+
+    test_cinter.push_ops("-1 prec");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.config.precision.is_none());
+
+    test_cinter.push_ops("0.5 prec");
+    assert!(test_cinter.process_ops().is_err());
+
+    test_cinter.push_ops("cls 4700 0.000000022 -12345.678 1e30 eng");
+    test_cinter.process_ops().unwrap();
+    assert!(display(&test_cinter) == vec!["4.7e3", "22e-9", "-12.345678e3", "1e30"]);
+
+    test_cinter.push_ops("si");
+    test_cinter.process_ops().unwrap();
+    assert!(display(&test_cinter) == vec!["4.7k", "22n", "-12.345678k", "1e30"]);
+
+    test_cinter.config.precision = Some(1);
+    assert!(display(&test_cinter) == vec!["4.7k", "22.0n", "-12.3k", "1.0e30"]);
+
+    test_cinter.push_ops("cls 1234567.89 -1234567 123 1e20 auto group -1 prec");
+    test_cinter.process_ops().unwrap();
+    assert!(display(&test_cinter) == vec!["1,234,567.89", "-1,234,567", "123", "1e20"]);
+
+    test_cinter.config.group = Some(' ');
+    assert!(display(&test_cinter)[0] == "1 234 567.89");
+
+    // decimal comma locale
+    let config: super::Config = super::Config { locale: Some("de_DE".to_string()), ..super::Config::default() };
+    let mut test_cinter = super::Interpreter::with_config(config).unwrap();
+
+    test_cinter.push_ops("3,14 2 x 1234567,5 group");
+    test_cinter.process_ops().unwrap();
+    assert!(display(&test_cinter) == vec!["6,28", "1.234.567,5"]);
+    test_cinter.config.group = Some(' ');
+    assert!(display(&test_cinter)[1] == "1 234 567,5");
+    assert!(super::Config { locale: Some("en_US".to_string()), ..super::Config::default() }.decimal_separator() == '.');
+  }
+
+  #[test]
+  fn test_integer() {
+    let config: super::Config = super::Config { integer: true, ..super::Config::default() };
+    let mut test_cinter = super::Interpreter::with_config(config).unwrap();
+
+    test_cinter.push_ops("7 2 / -7 2 / 2 126 ^ 30 !");
+    test_cinter.process_ops().unwrap();
+    let stack: Vec<String> = test_cinter.stack.iter().map(|element| element.to_string()).collect();
+    assert!(stack == vec!["3", "-3", "85070591730234615865843651857942052864", "265252859812191058636308480000000"]);
+
+    test_cinter.push_ops("2 127 ^");
+    assert!(test_cinter.process_ops() == Err(super::CompError::Located {
+      span: super::Span { index: 3, source: None, line: Some(1) },
+      error: Box::new(super::CompError::IntegerOverflow),
+    }));
+
+    test_cinter.push_ops("1 0 /");
+    assert!(test_cinter.process_ops().is_err());
+
+    test_cinter.push_ops("2.5 1 +");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_ieee() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("-1 bits dup frombits 12 mantissa 12 expnt 5e-324 expnt 1 ulp 1 0 nextafter");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[0].to_string() == "13830554455654793216");
+    assert!(test_cinter.stack[1] == super::Value::Float(-1.0));
+    assert!(test_cinter.stack[2] == super::Value::Float(1.5));
+    assert!(test_cinter.stack[3] == super::Value::Int(3));
+    assert!(test_cinter.stack[4] == super::Value::Int(-1074));
+    assert!(test_cinter.stack[5] == super::Value::Float(f64::EPSILON));
+    assert!(test_cinter.stack[6] == super::Value::Float(1.0 - f64::EPSILON / 2.0));
+
+    test_cinter.push_ops("-1 frombits");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_strict() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("nan isnan 1 0 / isinf -1 ln isfinite 2 isfinite");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == vec![super::Value::Int(1), super::Value::Int(1), super::Value::Int(0), super::Value::Int(1)]);
+
+    let config = super::Config { strict: true, ..super::Config::default() };
+    let mut test_cinter = super::Interpreter::with_config(config).unwrap();
+
+    test_cinter.push_ops("4 sqrt -1 ln 5");
+    let error = test_cinter.process_ops().unwrap_err();
+    assert!(error.to_string().starts_with("[ln] operation produced a non-finite result"));
+    assert!(test_cinter.stack == vec![super::Value::Float(2.0)]);
+  }
+
+  #[test]
+  fn test_tofrac() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("pi 0.001 tofrac -0.333 0.001 tofrac 0.75 0 tofrac 2.5 1 tofrac");
+    test_cinter.process_ops().unwrap();
+    let expected: Vec<super::Value> = [201, 64, -1, 3, 3, 4, 2, 1].iter().map(|i| super::Value::Int(*i)).collect();
+    assert!(test_cinter.stack == expected);
+  }
+
+  #[test]
+  fn test_pctchg() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("80 100 pctchg 100 80 pctchg 3 4 pctchg 1.5 3 pctchg 0 1 pctchg");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[0] == super::Value::Int(25));
+    assert!(test_cinter.stack[1] == super::Value::Int(-20));
+    assert!((test_cinter.stack[2].to_f64() - 100.0 / 3.0).abs() < 1e-12);
+    assert!(test_cinter.stack[3] == super::Value::Float(100.0));
+    assert!(test_cinter.stack[4] == super::Value::Float(f64::INFINITY));
+  }
+
+  #[test]
+  fn test_dms() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("30.5125 dms -0.5 dms");
+    test_cinter.process_ops().unwrap();
+    let expected: Vec<super::Value> = [30.0, 30.0, 45.0, 0.0, -30.0, 0.0].iter().map(|f| super::Value::Float(*f)).collect();
+    assert!(test_cinter.stack == expected);
+
+    test_cinter.push_ops("cls 30 30 45 ddeg 0 -30 0 ddeg");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == vec![super::Value::Float(30.5125), super::Value::Float(-0.5)]);
+  }
+
+  #[test]
+  fn test_roman() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1994 roman 3999 roman dup unroman \"iv\" unroman");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[0] == super::Value::Str("MCMXCIV".to_string()));
+    assert!(test_cinter.stack[1] == super::Value::Str("MMMCMXCIX".to_string()));
+    assert!(test_cinter.stack[2] == super::Value::Int(3999));
+    assert!(test_cinter.stack[3] == super::Value::Int(4));
+
+    for ops in ["0 roman", "4000 roman", "\"IIII\" unroman", "\"XM\" unroman", "5 unroman"] {
+      test_cinter.push_ops(ops);
+      assert!(matches!(test_cinter.process_ops(), Err(super::CompError::Located { .. })));
+    }
+
+    test_cinter.push_ops("\"X\" 1 +");
+    assert!(test_cinter.process_ops().unwrap_err().to_string().starts_with("value [X] is not a number"));
+  }
+
+  #[test]
+  fn test_chr() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("97 chr 0x1f600 chr ord \"A\" ord");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == vec![super::Value::Str("a".to_string()), super::Value::Int(0x1f600), super::Value::Int(65)]);
+
+    for ops in ["0xd800 chr", "-1 chr", "\"ab\" ord", "\"\" ord", "65 ord"] {
+      test_cinter.push_ops(ops);
+      assert!(test_cinter.process_ops().is_err());
+    }
+  }
+
+  #[test]
+  fn test_min_max() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("3 4 min 3 4 max -2.5 1 min 1/3 0.3 max 1 nan min 1 1i max");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[0] == super::Value::Int(3));
+    assert!(test_cinter.stack[1] == super::Value::Int(4));
+    assert!(test_cinter.stack[2] == super::Value::Float(-2.5));
+    assert!(test_cinter.format_value(&test_cinter.stack[3]) == "1/3");
+    assert!(test_cinter.stack[4].to_f64().is_nan());
+    assert!(test_cinter.stack[5].to_f64().is_nan());
+  }
+
+  #[test]
+  fn test_hypot() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("3 4 hypot 6e307 8e307 hypot 3e-200 4e-200 hypot");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == vec![super::Value::Float(5.0), super::Value::Float(1e308), super::Value::Float(5e-200)]);
+  }
+
+  #[test]
+  fn test_atan2() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1 1 atan2 -1 -1 atan2 0 -1 atan2");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == vec![
+      super::Value::Float(std::f64::consts::FRAC_PI_4),
+      super::Value::Float(-3.0 * std::f64::consts::FRAC_PI_4),
+      super::Value::Float(std::f64::consts::PI),
+    ]);
+
+    let config = super::Config { angle: super::AngleMode::Degrees, ..super::Config::default() };
+    let mut test_cinter = super::Interpreter::with_config(config).unwrap();
+
+    test_cinter.push_ops("1 -1 atan2");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == vec![super::Value::Float(135.0)]);
+  }
+
+  #[test]
+  fn test_nroot() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("-27 cbrt 64 cbrt -32 5 nroot 81 4 nroot 2 2 nroot -8 3 nroot");
+    test_cinter.process_ops().unwrap();
+    let expected: Vec<super::Value> = [-3.0, 4.0, -2.0, 3.0, 2f64.sqrt(), -2.0].iter().map(|f| super::Value::Float(*f)).collect();
+    assert!(test_cinter.stack == expected);
+
+    test_cinter.push_ops("-16 4 nroot");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack.last().unwrap().to_f64().is_nan());
+  }
+
+  #[test]
+  fn test_hyperbolic() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1 sinh 1 cosh 1 tanh 0 sinh 800 tanh");
+    test_cinter.process_ops().unwrap();
+    let expected: Vec<super::Value> = [1f64.sinh(), 1f64.cosh(), 1f64.tanh(), 0.0, 1.0].iter().map(|f| super::Value::Float(*f)).collect();
+    assert!(test_cinter.stack == expected);
+
+    test_cinter.push_ops("cls 1 asinh 1 acosh 0.5 atanh 0.5 acosh 1 atanh");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[0] == super::Value::Float(1f64.asinh()));
+    assert!(test_cinter.stack[1] == super::Value::Float(0.0));
+    assert!(test_cinter.stack[2] == super::Value::Float(0.5f64.atanh()));
+    assert!(test_cinter.stack[3].to_f64().is_nan());
+    assert!(test_cinter.stack[4] == super::Value::Float(f64::INFINITY));
+  }
+
+  #[test]
+  fn test_exponential() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1 e^ 2 3 exp 10 exp2 1e-10 expm1 1e-10 ln1p 0 e^");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[0] == super::Value::Float(std::f64::consts::E));
+    assert!(test_cinter.stack[1] == super::Value::Int(8));
+    assert!(test_cinter.stack[2] == super::Value::Float(1024.0));
+    assert!(test_cinter.stack[3] == super::Value::Float(1.00000000005e-10));
+    assert!(test_cinter.stack[4] == super::Value::Float(9.999999999500001e-11));
+    assert!(test_cinter.stack[5] == super::Value::Float(1.0));
+
+    test_cinter.push_ops("cls 1i pi x e^ re");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == vec![super::Value::Float(-1.0)]);
+  }
+
+  #[test]
+  fn test_gamma() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("5 gamma 0.5 gamma 0.5 ! 4.0 ! -2 gamma -3 ! 1000 lngamma 200 gamma 1000.5 ! 1e9 gamma 171.6 gamma");
+    test_cinter.process_ops().unwrap();
+    let sqrt_pi: f64 = std::f64::consts::PI.sqrt();
+    assert!(test_cinter.stack[0] == super::Value::Float(24.0));
+    assert!((test_cinter.stack[1].to_f64() - sqrt_pi).abs() < 1e-15);
+    assert!((test_cinter.stack[2].to_f64() - sqrt_pi / 2.0).abs() < 1e-15);
+    assert!(test_cinter.stack[3] == super::Value::Float(24.0));
+    assert!(test_cinter.stack[4].to_f64().is_nan());
+    assert!(test_cinter.stack[5].to_f64().is_nan());
+    assert!((test_cinter.stack[6].to_f64() - 5905.220423209181).abs() < 1e-9);
+    assert!(test_cinter.stack[7] == super::Value::Float(f64::INFINITY));
+    assert!(test_cinter.stack[8] == super::Value::Float(f64::INFINITY));
+    assert!(test_cinter.stack[9] == super::Value::Float(f64::INFINITY));
+    assert!(test_cinter.stack[10].to_f64().is_finite());
+  }
+
+  #[test]
+  fn test_lcm() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("4 6 lcm 0 5 lcm 4294967296 6442450944 lcm");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == vec![super::Value::Int(12), super::Value::Int(0), super::Value::Int(12884901888)]);
+
+    test_cinter.push_ops("-4 6 lcm");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_modpow() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("7 128 13 modpow 2 1000000 1000000007 modpow -2 3 5 modpow 5 0 1 modpow");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == vec![super::Value::Int(3), super::Value::Int(235042059), super::Value::Int(2), super::Value::Int(0)]);
+
+    for ops in ["2 3 0 modpow", "2 -1 5 modpow", "2.5 2 5 modpow"] {
+      test_cinter.push_ops(ops);
+      assert!(test_cinter.process_ops().is_err());
+    }
+  }
+
+  #[test]
+  fn test_modinv() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("3 11 modinv -3 11 modinv 5 1 modinv 65537 3120 modinv");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == vec![super::Value::Int(4), super::Value::Int(7), super::Value::Int(0), super::Value::Int(2753)]);
+
+    test_cinter.push_ops("4 6 modinv");
+    assert!(test_cinter.process_ops().unwrap_err().to_string().starts_with("value [4] has no inverse modulo [6]"));
+    test_cinter.push_ops("3 0 modinv");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_ncr_npr() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("52 5 ncr 10 3 npr 5 0 ncr 5 0 npr 3 5 ncr 3 5 npr 100 50 ncr");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[..6] == [2598960, 720, 1, 1, 0, 0].map(super::Value::Int));
+    assert!(test_cinter.stack[6].to_string() == "100891344545564193334812497256");
+
+    test_cinter.push_ops("cls 100000 50000 ncr");
+    test_cinter.process_ops().unwrap();
+    assert!(matches!(test_cinter.stack[0], super::Value::Float(f) if f.is_infinite()));
+  }
+
+  #[test]
+  fn test_primes() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("97 isprime 1 isprime 561 isprime 18446744073709551557 isprime -5 nextprime 100 nextprime 100 prevprime");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 0, 0, 1, 2, 101, 97].map(super::Value::Int));
+
+    test_cinter.push_ops("cls 360 factor");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [2, 2, 2, 3, 3, 5].map(super::Value::Int));
+
+    test_cinter.push_ops("cls 18446744073709551615 factor x_");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[0].to_string() == "18446744073709551615");
+
+    for ops in ["1 factor", "2 prevprime", "2.5 isprime"] {
+      test_cinter.push_ops(ops);
+      assert!(test_cinter.process_ops().is_err());
+    }
+  }
+
+  #[test]
+  fn test_fib() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("0 fib 1 fib 10 fib 0 lucas 1 lucas 10 lucas 100 fib");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[..6] == [0, 1, 55, 2, 1, 123].map(super::Value::Int));
+    assert!(test_cinter.stack[6].to_string() == "354224848179261915075");
+
+    test_cinter.push_ops("cls 10000 fib 10001 fib 9999 fib - -");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == vec![super::Value::Int(0)]);
+  }
+
+  #[test]
+  fn test_random() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    for _ in 0..100 {
+      test_cinter.push_ops("cls rand 6 1 randint -3 -3 randint");
+      test_cinter.process_ops().unwrap();
+      assert!((0.0..1.0).contains(&test_cinter.stack[0].to_f64()));
+      assert!((1..=6).contains(&(test_cinter.stack[1].to_f64() as i64)));
+      assert!(test_cinter.stack[2] == super::Value::Int(-3));
+    }
+
+    test_cinter.push_ops("cls 1 2 3 4 5 6 7 8 shuffle");
+    test_cinter.process_ops().unwrap();
+    let mut shuffled: Vec<f64> = test_cinter.stack.iter().map(|element| element.to_f64()).collect();
+    shuffled.sort_by(f64::total_cmp);
+    assert!(shuffled == vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+
+    test_cinter.push_ops("1.5 3 randint");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_seed() {
+    let config = super::Config { seed: Some(7), ..super::Config::default() };
+    let mut test_cinter = super::Interpreter::with_config(config).unwrap();
+
+    test_cinter.push_ops("rand 1 100 randint 1 2 3 4 5 shuffle");
+    test_cinter.process_ops().unwrap();
+    let seeded: Vec<super::Value> = test_cinter.stack.clone();
+
+    test_cinter.push_ops("cls 7 seed rand 1 100 randint 1 2 3 4 5 shuffle");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == seeded);
+
+    test_cinter.push_ops("cls 8 seed rand");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[0] != seeded[0]);
+
+    test_cinter.push_ops("-1 seed");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_distributions() {
+    let config = super::Config { seed: Some(1), ..super::Config::default() };
+    let mut test_cinter = super::Interpreter::with_config(config).unwrap();
+
+    // sample means are close to the distribution means
+    for (ops, mean) in [("randn", 0.0), ("4 randexp", 0.25), ("3 randpois", 3.0), ("50 randpois", 50.0)] {
+      test_cinter.push_ops(&format!("cls {}", [ops; 10000].join(" ")));
+      test_cinter.process_ops().unwrap();
+      let sum: f64 = test_cinter.stack.iter().map(|element| element.to_f64()).sum();
+      assert!((sum / 10000.0 - mean).abs() < 0.05 * mean.max(1.0), "mean of [{ops}] is {}", sum / 10000.0);
+    }
+    assert!(test_cinter.stack.iter().all(|element| matches!(element, super::Value::Int(k) if *k >= 0)));
+
+    for ops in ["0 randexp", "-1 randpois", "inf randpois"] {
+      test_cinter.push_ops(ops);
+      assert!(test_cinter.process_ops().is_err());
+    }
+  }
+
+  #[test]
+  fn test_sign() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("-3 sign 0 sign 5 sign -2.5 sign 0.0 sign -1/3 sign 3+4i sign");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[..3] == [-1, 0, 1].map(super::Value::Int));
+    assert!(test_cinter.stack[3] == super::Value::Float(-1.0));
+    assert!(test_cinter.stack[4] == super::Value::Float(0.0));
+    assert!(test_cinter.stack[5] == super::Value::Int(-1));
+    assert!(test_cinter.format_value(&test_cinter.stack[6]) == "0.6+0.8i");
+  }
+
+  #[test]
+  fn test_clamp() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("12 0 10 clamp -0.5 0 1 clamp 0.5 0 1 clamp nan 0 1 clamp");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[0] == super::Value::Int(10));
+    assert!(test_cinter.stack[1] == super::Value::Int(0));
+    assert!(test_cinter.stack[2] == super::Value::Float(0.5));
+    assert!(test_cinter.stack[3].to_f64().is_nan());
+
+    test_cinter.push_ops("5 10 0 clamp");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_lerp() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("10 20 0.25 lerp 10 20 1.5 lerp 10 20 12.5 invlerp 50 32 212 0 100 remap 5 0 10 1 -1 remap");
+    test_cinter.process_ops().unwrap();
+    let expected: Vec<super::Value> = [12.5, 25.0, 0.25, 10.0, 0.0].iter().map(|f| super::Value::Float(*f)).collect();
+    assert!(test_cinter.stack == expected);
+  }
+
+  #[test]
+  fn test_floor_div() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("7 2 // -7 2 // 7 2 divmod -7 2 divmod 7 -2 divmod");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [3, -4, 3, 1, -4, 1, -4, -1].map(super::Value::Int));
+
+    test_cinter.push_ops("cls 7.5 2 divmod 1 0 //");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[..2] == [3.0, 1.5].map(super::Value::Float));
+    assert!(test_cinter.stack[2] == super::Value::Float(f64::INFINITY));
+  }
+
+  #[test]
+  fn test_rounding() {
+    let ops: &str = "2.5 round -2.5 round 3.5 round 2.675 2 roundn 1250 -2 roundn -2.7 round";
+    for (rounding, expected) in [
+      (super::Rounding::HalfUp, ["3", "-3", "4", "2.68", "1300", "-3"]),
+      (super::Rounding::HalfEven, ["2", "-2", "4", "2.68", "1200", "-3"]),
+      (super::Rounding::TowardZero, ["2", "-2", "3", "2.67", "1200", "-2"]),
+    ] {
+      let config = super::Config { rounding, ..super::Config::default() };
+      let mut test_cinter = super::Interpreter::with_config(config).unwrap();
+
+      test_cinter.push_ops(ops);
+      test_cinter.process_ops().unwrap();
+      let stack: Vec<String> = test_cinter.stack.iter().map(|element| test_cinter.format_value(element)).collect();
+      assert!(stack == expected, "{rounding:?} rounding results in {stack:?}");
+    }
+
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+    test_cinter.push_ops("1/3 2 roundn 1234 2 roundn");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.format_value(&test_cinter.stack[0]) == "33/100");
+    assert!(test_cinter.stack[1] == super::Value::Int(1234));
+  }
+
+  #[test]
+  fn test_bitwise() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("12 10 and 12 10 or 12 10 xor 0 not -6 3 and 1 8 shl 256 4 shr -5 1 shr");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [8, 14, 6, -1, 2, 256, 16, -3].map(super::Value::Int));
+
+    test_cinter.push_ops("cls 1 100 shl 0xffffffffffffffffffffffffffffffff 0xf0 and");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[0].to_string() == "1267650600228229401496703205376");
+    assert!(test_cinter.stack[1] == super::Value::Int(0xf0));
+
+    for ops in ["1.5 1 and", "1 -1 shl", "1 2 and 3 shr 2.5 not"] {
+      test_cinter.push_ops(ops);
+      assert!(test_cinter.process_ops().is_err());
+    }
+
+    let config = super::Config { integer: true, ..super::Config::default() };
+    let mut test_cinter = super::Interpreter::with_config(config).unwrap();
+    test_cinter.push_ops("1 127 shl");
+    assert!(test_cinter.process_ops() == Err(super::CompError::IntegerOverflow.at(&super::Span { index: 3, source: None, line: Some(1) })));
+  }
+
+  #[test]
+  fn test_stack_words() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1 2 over 3 nip 4 tuck 0 pick 4 pick depth");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 2, 4, 3, 4, 4, 2, 7].map(super::Value::Int));
+
+    test_cinter.push_ops("cls 1 2 2 pick");
+    let error = test_cinter.process_ops().unwrap_err();
+    assert!(error.to_string().starts_with("[pick] operation called without at least 4 element(s) on stack"));
+
+    test_cinter.push_ops("cls 1 over");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_rolln() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1 2 3 4 3 rolln");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 4, 2, 3].map(super::Value::Int));
+
+    test_cinter.push_ops("3 rotn 4 rotn 0 rolln");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [2, 3, 4, 1].map(super::Value::Int));
+
+    test_cinter.push_ops("5 rolln");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_dropn_dupn() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1 2 3 2 dupn 0 dupn 0 dropn 3 dropn");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 2].map(super::Value::Int));
+
+    test_cinter.push_ops("3 dupn");
+    assert!(test_cinter.process_ops().is_err());
+    test_cinter.push_ops("3 dropn");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_sort() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("3 nan 1/2 -2.5 18446744073709551616 1 sort");
+    test_cinter.process_ops().unwrap();
+    let stack: Vec<String> = test_cinter.stack.iter().map(|element| test_cinter.format_value(element)).collect();
+    assert!(stack == ["-2.5", "1/2", "1", "3", "18446744073709551616", "NaN"]);
+
+    test_cinter.push_ops("sortd");
+    test_cinter.process_ops().unwrap();
+    let stack: Vec<String> = test_cinter.stack.iter().map(|element| test_cinter.format_value(element)).collect();
+    assert!(stack == ["NaN", "18446744073709551616", "3", "1", "1/2", "-2.5"]);
+
+    test_cinter.push_ops("1+2i sort");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_rev() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("rev 1 2 3 4 rev");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [4, 3, 2, 1].map(super::Value::Int));
+  }
+
+  #[test]
+  fn test_uniq() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("3 1 3.0 1/2 0.5 3 \"x\" \"x\" uniq");
+    test_cinter.process_ops().unwrap();
+    let stack: Vec<String> = test_cinter.stack.iter().map(|element| test_cinter.format_value(element)).collect();
+    assert!(stack == ["3", "1", "1/2", "x"]);
+
+    test_cinter.push_ops("cls 1 1.05 0.9 2 1+0.01i 0.1 uniqt");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 2].map(super::Value::Int));
+
+    test_cinter.push_ops("-1 uniqt");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_sum_mean_median() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    for (ops, result) in [("5 sum", "5"), ("1 2 3 4 sum", "10"), ("1 2 3 4 mean", "2.5"), ("1/2 1 mean", "3/4"),
+                          ("3 1 4 1 5 median", "3"), ("3 1 4 1 median", "2"), ("1/3 6 -2 median", "1/3")] {
+      test_cinter.push_ops(&format!("cls {ops}"));
+      test_cinter.process_ops().unwrap();
+      assert!(test_cinter.stack.len() == 1);
+      assert!(test_cinter.format_value(&test_cinter.stack[0]) == result);
+    }
+
+    test_cinter.push_ops("cls sum");
+    assert!(test_cinter.process_ops().is_err());
+    test_cinter.push_ops("1 2i median");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_variance() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    for (ops, result) in [("2 4 4 4 5 5 7 9 varp", 4.0), ("2 4 4 4 5 5 7 9 sdp", 2.0), ("1 3 var", 2.0),
+                          ("1 3 sd", std::f64::consts::SQRT_2), ("5 varp", 0.0), ("1/2 3/2 varp", 0.25)] {
+      test_cinter.push_ops(&format!("cls {ops}"));
+      test_cinter.process_ops().unwrap();
+      assert!(test_cinter.stack == [super::Value::Float(result)]);
+    }
+
+    test_cinter.push_ops("cls 5 var");
+    assert!(test_cinter.process_ops().is_err());
+    test_cinter.push_ops("1 2i sd");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_min_max_all() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("3 1/2 4 -2.5 max_ 3 1/2 4 -2.5 min_");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Float(-2.5)]);
+
+    test_cinter.push_ops("cls 3 1/2 4 max_");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(4)]);
+
+    test_cinter.push_ops("min_");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_count() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("count 5 6 7 count");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [0, 5, 6, 7, 4].map(super::Value::Int));
+
+    test_cinter.push_ops("sa +_ a /");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Float(4.5)]);
+  }
+
+  #[test]
+  fn test_sequences() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1 9 2 range 3 1 -1 range 1 0 1 range 5 5 3 range");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 3, 5, 7, 9, 3, 2, 1, 5].map(super::Value::Int));
+
+    test_cinter.push_ops("cls 0 1 5 linspace 1/3 1 3 linspace 0.5 1.5 2 linspace 7 8 1 linspace 7 8 0 linspace");
+    test_cinter.process_ops().unwrap();
+    let stack: Vec<String> = test_cinter.stack.iter().map(|element| test_cinter.format_value(element)).collect();
+    assert!(stack == ["0", "0.25", "0.5", "0.75", "1", "1/3", "2/3", "1", "0.5", "1.5", "7"]);
+
+    test_cinter.push_ops("1 2 0 range");
+    assert!(test_cinter.process_ops().is_err());
+    test_cinter.push_ops("1 100000000000 1 range");
+    assert!(test_cinter.process_ops() == Err(super::CompError::StepLimit(super::Config::default().max_ops)
+      .at(&super::Span { index: 4, source: None, line: Some(1) })));
+  }
+
+  #[test]
+  fn test_map() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("fn sq dup x end 1 2 3 4 map sq +_ map chs map dup");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [-30, -30].map(super::Value::Int));
+
+    test_cinter.push_ops("map drop map nosuchfn");
+    assert!(test_cinter.process_ops() == Err(super::CompError::UnknownFunction("nosuchfn".to_string())
+      .at(&super::Span { index: 3, source: None, line: Some(1) })));
+
+    test_cinter.push_ops("5 map +");
+    assert!(test_cinter.process_ops().is_err());
+    assert!(test_cinter.stack == [super::Value::Int(5)]);
+
+    test_cinter.push_ops("fn f map f end f");
+    assert!(matches!(test_cinter.process_ops(), Err(super::CompError::Located { error, .. })
+      if *error == super::CompError::NestingLimit(256)));
+  }
+
+  #[test]
+  fn test_filter() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1 20 1 range filter isprime fn odd 2 mod end filter odd");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [3, 5, 7, 11, 13, 17, 19].map(super::Value::Int));
+
+    test_cinter.push_ops("cls 1 nan 0 1/2 0.0 \"\" \"x\" filter dup");
+    test_cinter.process_ops().unwrap();
+    let stack: Vec<String> = test_cinter.stack.iter().map(|element| test_cinter.format_value(element)).collect();
+    assert!(stack == ["1", "1/2", "x"]);
+
+    test_cinter.push_ops("filter drop");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack.is_empty());
+  }
+
+  #[test]
+  fn test_fold() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1 2 3 10 fold - 0 fold - fn sumsq dup x + end 1 2 3 0 fold sumsq");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(30)]);
+
+    test_cinter.push_ops("cls 10 1 2 reduce - 5 reduce max 2 3 4 reduce lcm");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(84)]);
+
+    test_cinter.push_ops("cls fold +");
+    assert!(test_cinter.process_ops().is_err());
+    test_cinter.push_ops("1 2 reduce cls");
+    assert!(test_cinter.process_ops().is_err());
+    assert!(test_cinter.stack == [1, 2].map(super::Value::Int));
+  }
+
+  #[test]
+  fn test_zip() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1 2 3 4 5 6 zipx +_ 1 zip+");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(33)]);
+
+    test_cinter.push_ops("cls 10 20 3 4 zipwith -");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [7, 16].map(super::Value::Int));
+
+    test_cinter.push_ops("cls zipwith - zip+");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack.is_empty());
+
+    test_cinter.push_ops("1 2 3 zip+");
+    assert!(test_cinter.process_ops().unwrap_err().to_string() == "[zip+] operation requires an even number of elements on stack (3 found) (op 4, line 1)");
+    assert!(test_cinter.stack == [1, 2, 3].map(super::Value::Int));
+  }
+
+  #[test]
+  fn test_wstack_rstack() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+    let path: String = std::env::temp_dir().join("comp_test_stack.txt").to_string_lossy().to_string();
+
+    let stack: Vec<super::Value> = vec![super::Value::Int(-3), super::Value::Float(2.0), super::Value::Float(f64::INFINITY),
+                                        super::Value::parse("1/3").unwrap(), super::Value::parse("18446744073709551616").unwrap(),
+                                        super::Value::parse("3-4i").unwrap(), super::Value::Str("XIV".to_string())];
+    test_cinter.stack = stack.clone();
+    test_cinter.push_ops(&format!("wstack {path} cls 0 rstack {path}"));
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[0] == super::Value::Int(0));
+    assert!(test_cinter.stack[1..] == stack[..]);
+
+    std::fs::write(&path, "1 2 eggs").unwrap();
+    test_cinter.push_ops(&format!("rstack {path}"));
+    assert!(test_cinter.process_ops().is_err());
+    std::fs::remove_file(&path).unwrap();
+  }
+
+  #[test]
+  fn test_take_skip() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1 10 1 range 5 take 1 skip 0 skip 3 take");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [7, 8, 9].map(super::Value::Int));
+
+    test_cinter.push_ops("0 take 1 take");
+    assert!(test_cinter.process_ops().is_err());
+    assert!(test_cinter.stack.is_empty());
+  }
+
+  #[test]
+  fn test_keep() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("3 4 keep + keep chs");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [3, 4, 7, -7].map(super::Value::Int));
+
+    test_cinter.push_ops("cls fn hyp dup x swap dup x + sqrt end 3 4 keep hyp 1 2 keep +_");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(3), super::Value::Int(4), super::Value::Float(5.0),
+                                  super::Value::Int(1), super::Value::Int(2), super::Value::Float(15.0)]);
+
+    test_cinter.push_ops("cls 1 keep +");
+    assert!(test_cinter.process_ops().is_err());
+    assert!(test_cinter.stack == [super::Value::Int(1)]);
+
+    // operands of a function with declared parameters
+    test_cinter.push_ops("cls fn dist a b | a b - abs end 1 5 8 keep dist");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 5, 8, 3].map(super::Value::Int));
+
+    test_cinter.push_ops("cls 1 keep dist");
+    assert!(test_cinter.process_ops().is_err());
+    assert!(test_cinter.stack == [super::Value::Int(1)]);
+  }
+
+  #[test]
+  fn test_again() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("again");
+    assert!(test_cinter.process_ops() == Err(super::CompError::NoHistory("again".to_string())
+      .at(&super::Span { index: 1, source: None, line: Some(1) })));
+
+    test_cinter.push_ops("3 2 x again again 10 - 2 redo 0 redo");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(-6)]);
+
+    test_cinter.push_ops("cls fn inc 1 + end 5 inc again 3 redo 16 sqrt again");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(10), super::Value::Float(2.0)]);
+
+    test_cinter.push_ops("cls fn sq dup x end 3 sq again");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(81)]);
+  }
+
+  #[test]
+  fn test_print() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("pstack 1 2 print pstack");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 2].map(super::Value::Int));
+    assert!(test_cinter.output == ["2", "1", "2"]);
+
+    test_cinter.push_ops("cls print");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_pairs() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1 2 2dup 2swap 5 6 2over 2drop 2swap");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 2, 5, 6, 1, 2].map(super::Value::Int));
+
+    test_cinter.push_ops("cls 1 2 3 2swap");
+    assert!(test_cinter.process_ops().is_err());
+    test_cinter.push_ops("cls 1 2drop");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_swapn() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1 2 3 4 3 swapn 0 swapn 1 swapn");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [4, 2, 1, 3].map(super::Value::Int));
+
+    test_cinter.push_ops("4 swapn");
+    assert!(test_cinter.process_ops() == Err(super::CompError::StackUnderflow { op: "swapn".to_string(), depth: 6 }
+      .at(&super::Span { index: 2, source: None, line: Some(1) })));
+  }
+
+  #[test]
+  fn test_undo() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("undo");
+    assert!(test_cinter.process_ops() == Err(super::CompError::NoHistory("undo".to_string())
+      .at(&super::Span { index: 1, source: None, line: Some(1) })));
+
+    test_cinter.push_ops("1 2 + undo 3 sa 4 5 x undo undo undo undo a");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(1), super::Value::Int(2), super::Value::Int(3), super::Value::Float(0.0)]);
+
+    test_cinter.push_ops("cls 5 4 3 2 1 sort 2 dropn rev undo undo undo undo");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [5, 4, 3, 2, 1].map(super::Value::Int));
+
+    test_cinter.push_ops("cls fn sq dup x end 3 sq");
+    test_cinter.process_ops().unwrap();
+    test_cinter.push_ops("undo");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(3)]);
+
+    // functions are undone down to the deepest element they change, and
+    // variables to their previous values
+    test_cinter.push_ops("cls 1 store k fn f a | drop drop a 2 store k 3 store j end 1 2 3 4 f");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 4].map(super::Value::Int));
+    test_cinter.push_ops("undo recall k");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 2, 3, 4, 1].map(super::Value::Int));
+    assert!(test_cinter.variables() == [("k", &super::Value::Int(1))]);
+
+    let path: String = std::env::temp_dir().join("comp_test_undo.json").to_string_lossy().to_string();
+    test_cinter.push_ops(&format!("cls 7 save {path} cls 1 2 load {path} undo"));
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 2].map(super::Value::Int));
+    std::fs::remove_file(&path).unwrap();
+
+    test_cinter.push_ops("cls 1 sqrt \"x\" + undo undo");
+    assert!(test_cinter.process_ops().is_err());
+    test_cinter.push_ops("undo");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Float(1.0), super::Value::Str("x".to_string())]);
+  }
+
+  #[test]
+  fn test_if() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("5 1 if chs 3 0 if chs 7 nan if chs 4 \"x\" ifelse sqrt chs 4 0.0 ifelse sqrt chs");
+    test_cinter.process_ops().unwrap();
+    let stack: Vec<String> = test_cinter.stack.iter().map(|element| test_cinter.format_value(element)).collect();
+    assert!(stack == ["-5", "3", "7", "2", "-4"]);
+
+    // recursion with a terminating condition
+    test_cinter.push_ops("cls fn fact dup 1 - dup 1 - if fact end 6 fact x_");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(720)]);
+
+    test_cinter.push_ops("if chs");
+    assert!(test_cinter.process_ops().is_err());
+    test_cinter.push_ops("cls if chs");
+    assert!(test_cinter.process_ops().is_err());
+    test_cinter.push_ops("cls 1 ifelse chs");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_comparisons() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("3 4 < 3 4 > 4 4 <= 3 4 >= 1/3 0.3 > 18446744073709551616 18446744073709551615 >");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 0, 1, 0, 1, 1].map(super::Value::Int));
+
+    test_cinter.push_ops("cls 1/2 0.5 == 2 2.0 != nan nan == nan nan != nan 1 < 1+2i 1+2i == 1+2i 1 == \"x\" \"x\" == \"x\" 0 !=");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 0, 0, 1, 0, 1, 0, 1, 1].map(super::Value::Int));
+
+    test_cinter.push_ops("\"x\" 1 <");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_logical() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("1 1 land 1 0 land 0 0 lor 0 -2 lor 2 lnot 0 lnot nan lnot 1 1 lxor 1/2 0 lxor");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 0, 0, 1, 0, 1, 1, 0, 1].map(super::Value::Int));
+
+    test_cinter.push_ops("cls fn digit dup 0 >= swap 9 <= land end -1 3 12 9 filter digit");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [3, 9].map(super::Value::Int));
+
+    test_cinter.push_ops("cls 1 land");
+    assert!(test_cinter.process_ops().is_err());
+  }
+
+  #[test]
+  fn test_while() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("fn small dup 100 < end fn sq dup x end 2 while small sq 0 5 while swap drop");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [256, 5].map(super::Value::Int));
+
+    test_cinter.push_ops("cls fn inexact dup dup x 2 - abs 1e-12 > end fn improve dup 2 swap / + 2 / end 1.0 while inexact improve");
+    test_cinter.process_ops().unwrap();
+    assert!((test_cinter.pop_stack_f().unwrap() - std::f64::consts::SQRT_2).abs() < 1e-12);
+
+    let config = super::Config { max_ops: 1000, ..super::Config::default() };
+    let mut test_cinter = super::Interpreter::with_config(config).unwrap();
+    test_cinter.push_ops("1 while dup dup");
+    assert!(matches!(test_cinter.process_ops(), Err(super::CompError::Located { error, .. })
+      if *error == super::CompError::StepLimit(1000)));
+  }
+
+  #[test]
+  fn test_times() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("fn sq dup x end 2 3 times sq 7 0 times sq 1 5 times dup");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [256, 7, 1, 1, 1, 1, 1, 1].map(super::Value::Int));
+
+    test_cinter.push_ops("-1 times sq");
+    assert!(test_cinter.process_ops().is_err());
+
+    // the function can consume elements below the count
+    test_cinter.push_ops("cls 1 2 3 2 times drop undo");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [1, 2, 3, 2].map(super::Value::Int));
+
+    let config = super::Config { max_ops: 1000, ..super::Config::default() };
+    let mut test_cinter = super::Interpreter::with_config(config).unwrap();
+    test_cinter.push_ops("1 1000000000000000000000 times dup");
+    assert!(matches!(test_cinter.process_ops(), Err(super::CompError::Located { error, .. })
+      if *error == super::CompError::StepLimit(1000)));
+  }
+
+  #[test]
+  fn test_for() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("fn sqsum dup x + end 0 1 10 for sqsum 1 1 5 for x 7 3 1 for drop -2 0 for chs");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [385, 120, 7, 2, 1, 0].map(super::Value::Int));
+
+    test_cinter.push_ops("1 2.5 for drop");
+    assert!(test_cinter.process_ops().is_err());
+
+    // the function can consume elements below the bounds
+    test_cinter.push_ops("cls 5 6 7 1 2 for 2drop undo");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [5, 6, 7, 1, 2].map(super::Value::Int));
+  }
+
+  #[test]
+  fn test_variables() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("0.05 store rate 2 store x 1000 1 recall rate + x recall x 7 store x recall x");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Float(1050.0), super::Value::Int(2), super::Value::Int(7)]);
+    assert!(test_cinter.variables() == [("rate", &super::Value::Float(0.05)), ("x", &super::Value::Int(7))]);
+
+    // undo restores the previous value
+    test_cinter.push_ops("cls 9 store x undo undo recall x");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(7)]);
+
+    test_cinter.push_ops("recall y");
+    assert!(test_cinter.process_ops().unwrap_err().to_string() == "variable [y] is not defined (no value stored) (op 1, line 1)");
+
+    test_cinter.push_ops("cls store z");
+    assert!(test_cinter.process_ops().is_err());
+
+    test_cinter.push_ops("recall x store");
+    assert!(test_cinter.check() == [super::CompError::MissingArgument("store".to_string()).at(&test_cinter.ops[2].span)]);
+  }
+
+  #[test]
+  fn test_local() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    // each call has its own local variables
+    test_cinter.push_ops("fn tenfold local k recall k 10 x end fn f local k recall k tenfold recall k + end 3 f");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(33)]);
+    assert!(test_cinter.variables().is_empty());
+
+    // recursive calls and loop functions
+    test_cinter.push_ops("cls fn fact dup 1 > if rec end fn rec local n recall n 1 - fact recall n x end 5 fact");
+    test_cinter.push_ops("fn more recall i recall n <= end fn add recall s recall i + store s recall i 1 + store i end");
+    test_cinter.push_ops("fn sumto local n 0 local s 1 local i while more add recall s end 10 sumto");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [120, 55].map(super::Value::Int));
+
+    // local variables are not defined after the call
+    test_cinter.push_ops("fn g local k end 1 g recall k");
+    assert!(test_cinter.process_ops().is_err());
+
+    // called functions do not see the local variables of the caller
+    test_cinter.push_ops("fn h recall k end fn f2 local k h 1 + end 4 f2");
+    assert!(test_cinter.process_ops().is_err());
+
+    // tail calls do not accumulate local variables
+    test_cinter.push_ops("cls fn down dup 0 > if next end fn next local n recall n 1 - down end 10000 down");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(0)] && test_cinter.frames.is_empty());
+
+    test_cinter.push_ops("2 local k recall k");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.variables() == [("k", &super::Value::Int(2))]);
+  }
+
+  #[test]
+  fn test_params() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("fn hyp a b | a dup x b dup x + sqrt end 3 4 hyp 7 a");
+    test_cinter.push_ops("fn sub2 a b | a b - end 10 3 sub2 fn f n | n local m m n x end 5 f");
+    test_cinter.process_ops().unwrap();
+    let stack: Vec<String> = test_cinter.stack.iter().map(|element| test_cinter.format_value(element)).collect();
+    assert!(stack == ["5", "7", "0", "7", "25"]);
+
+    test_cinter.push_ops("cls 1 hyp");
+    assert!(test_cinter.process_ops().unwrap_err().to_string() == "[hyp] operation called without at least 2 element(s) on stack (op 3, line 1)");
+
+    // parameters and local variables are known names
+    test_cinter.push_ops("cls fn g p | p local q q p + end 1 g hyp");
+    assert!(test_cinter.check() == [super::CompError::StackUnderflow { op: "hyp".to_string(), depth: 2 }.at(&test_cinter.ops[14].span)]);
+  }
+
+  #[test]
+  fn test_recursion() {
+    let config: super::Config = super::Config::parse("max_depth = 50").unwrap();
+    let mut test_cinter = super::Interpreter::with_config(config).unwrap();
+
+    // tail calls do not add a level
+    test_cinter.push_ops("fn tri dup 0 > if rec end fn rec dup 1 - tri + end fn down dup 0 > if next end fn next 1 - down end");
+    test_cinter.push_ops("40 tri 1000 down");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [820, 0].map(super::Value::Int));
+
+    test_cinter.push_ops("cls 60 tri");
+    assert!(matches!(test_cinter.process_ops(), Err(super::CompError::Located { error, .. })
+      if *error == super::CompError::NestingLimit(50)));
+
+    // nested calls of commands that take functions are also limited
+    test_cinter.push_ops("fn f map f end 1 f");
+    assert!(matches!(test_cinter.process_ops(), Err(super::CompError::Located { error, .. })
+      if *error == super::CompError::NestingLimit(50)));
+
+    // the call stack is discarded after an error
+    test_cinter.push_ops("cls 40 tri");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(820)]);
+  }
+
+  #[test]
+  fn test_quotation() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("[ dup x ]");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack[0].to_string() == "[ dup x ]");
+
+    test_cinter.push_ops("3 swap call 5 [ [ 1 + ] ] call call 5 0 ifelse [ chs ] [ 10 x ] 2 while [ dup 100 < ] [ dup x ]");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [9, 6, 50, 256].map(super::Value::Int));
+
+    // quotations use the parameters of the enclosing function
+    test_cinter.push_ops("cls fn scale k | map [ k x ] end 1 2 3 10 scale");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [10, 20, 30].map(super::Value::Int));
+
+    test_cinter.push_ops("[ 1 2");
+    assert!(test_cinter.process_ops().unwrap_err().to_string() == "quotation is missing a terminating []] (op 1, line 1)");
+    test_cinter.push_ops("cls 1 call");
+    assert!(test_cinter.process_ops().is_err());
+    test_cinter.push_ops("cls [ 1 ] 2 +");
+    assert!(test_cinter.process_ops().is_err());
+
+    test_cinter.push_ops("cls map [ dup foo ] [ 1");
+    assert!(test_cinter.check() == [
+      super::CompError::UnknownExpression("foo".to_string()).at(&test_cinter.ops[4].span),
+      super::CompError::IncompleteQuotation.at(&test_cinter.ops[6].span),
+    ]);
+  }
+
+  #[test]
+  fn test_include() {
+    let dir: std::path::PathBuf = std::env::temp_dir().join("comp_test_include");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("lib.comp"), "fn square dup x end\nfn cube dup square x end\n").unwrap();
+    std::fs::write(dir.join("main.comp"), "include lib.comp\n3 cube\n").unwrap();
+    std::fs::write(dir.join("loop.comp"), "1 include loop.comp\n").unwrap();
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    // relative to the including file
+    let main: String = dir.join("main.comp").display().to_string();
+    test_cinter.push_file_ops(&std::fs::read_to_string(&main).unwrap(), &main);
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(27)]);
+
+    test_cinter.push_ops(&format!("include {}", dir.join("loop.comp").display()));
+    let result: Result<(), super::CompError> = test_cinter.process_ops();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(matches!(result, Err(super::CompError::Located { error, .. }) if matches!(*error, super::CompError::FileError { .. })));
+
+    test_cinter.push_ops("include");
+    assert!(test_cinter.check() == [super::CompError::MissingArgument("include".to_string()).at(&test_cinter.ops[0].span)]);
+  }
+
+  #[test]
+  fn test_prelude() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("3 square 2 cube 3 4 avg 3 4 hyp 2 -3 -5 quadratic");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [
+      super::Value::Int(9), super::Value::Int(8), super::Value::Float(3.5), super::Value::Float(5.0),
+      super::Value::Float(2.5), super::Value::Float(-1.0),
+    ]);
+
+    // standard functions can be redefined
+    test_cinter.push_ops("cls fn square 2 x end 3 square");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(6)]);
+  }
+
+  #[test]
+  fn test_alias() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_ops("alias neg chs alias sq square 3 neg 5 sq");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack == [super::Value::Int(-3), super::Value::Int(25)]);
+
+    test_cinter.push_ops("alias neg foo");
+    assert!(test_cinter.check() == [super::CompError::UnknownFunction("foo".to_string()).at(&test_cinter.ops[2].span)]);
+    assert!(test_cinter.process_ops().unwrap_err().to_string() == "[foo] is not a command or user-defined function (op 1, line 1)");
+  }
+
+  #[test]
+  fn test_line_comments() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    test_cinter.push_file_ops("#!/usr/bin/env -S comp -f\n# square\n3 dup x # 9\n#x\n4 +\n", "script.comp");
+    assert!(test_cinter.ops.iter().map(|token| token.text.as_str()).collect::<Vec<&str>>() == ["3", "dup", "x", "4", "+"]);
+    assert!(test_cinter.ops[3].span.line == Some(5));
+
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.pop_stack_f().unwrap() == 13.0);
+  }
+
+  #[test]
+  fn test_undo_large() {
+    let mut test_cinter = super::Interpreter::with_config(super::Config::default()).unwrap();
+
+    // undo history does not copy all variables or the whole stack for each
+    // operation
+    let stores: Vec<String> = (0..2000).map(|i| format!("{i} store v{i}")).collect();
+    test_cinter.push_ops(&stores.join(" "));
+    test_cinter.push_ops("1 10000 1 range");
+    test_cinter.push_ops(&"dup square drop ".repeat(10000));
+    let start: std::time::Instant = std::time::Instant::now();
+    test_cinter.process_ops().unwrap();
+    assert!(start.elapsed() < std::time::Duration::from_secs(10));
+    assert!(test_cinter.stack.len() == 10000 && test_cinter.variables().len() == 2000);
+
+    test_cinter.push_ops("undo undo undo");
+    test_cinter.process_ops().unwrap();
+    assert!(test_cinter.stack.len() == 10000);
   }
 }
@@ -0,0 +1,298 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::error::CompError;
+
+// languages written with a decimal comma (ISO 639-1 codes)
+const DECIMAL_COMMA_LANGUAGES: [&str; 28] = [
+  "bg", "ca", "cs", "da", "de", "el", "es", "et", "fi", "fr", "hr", "hu", "id", "it",
+  "lt", "lv", "nb", "nl", "nn", "no", "pl", "pt", "ro", "ru", "sk", "sl", "sv", "tr",
+];
+
+/// Angle unit used by the trigonometric commands.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum AngleMode {
+  #[default]
+  #[serde(rename = "rad")]
+  Radians,
+  #[serde(rename = "deg")]
+  Degrees,
+}
+
+impl FromStr for AngleMode {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "rad" => Ok(AngleMode::Radians),
+      "deg" => Ok(AngleMode::Degrees),
+      _ => Err(format!("unknown angle mode [{s}] (expected deg or rad)")),
+    }
+  }
+}
+
+/// Base in which integer values are displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Base {
+  #[default]
+  Dec,
+  Hex,
+  Bin,
+  Oct,
+}
+
+impl FromStr for Base {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "dec" => Ok(Base::Dec),
+      "hex" => Ok(Base::Hex),
+      "bin" => Ok(Base::Bin),
+      "oct" => Ok(Base::Oct),
+      _ => Err(format!("unknown base [{s}] (expected dec, hex, bin, or oct)")),
+    }
+  }
+}
+
+/// Notation in which floating point values are displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+  /// Fixed notation, or scientific notation for very large or small values.
+  #[default]
+  Auto,
+  /// Fixed notation (e.g., 1230000).
+  Fix,
+  /// Scientific notation (e.g., 1.23e6).
+  Sci,
+  /// Engineering notation with exponents in multiples of three (e.g.,
+  /// 4.7e3).
+  Eng,
+  /// Engineering notation with SI prefixes (e.g., 4.7k or 22n).
+  Si,
+}
+
+impl FromStr for Format {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "auto" => Ok(Format::Auto),
+      "fix" => Ok(Format::Fix),
+      "sci" => Ok(Format::Sci),
+      "eng" => Ok(Format::Eng),
+      "si" => Ok(Format::Si),
+      _ => Err(format!("unknown format [{s}] (expected auto, fix, sci, eng, or si)")),
+    }
+  }
+}
+
+/// Rounding mode of the `round` and `roundn` commands.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rounding {
+  /// Round halfway values away from zero (e.g., 2.5 to 3 and -2.5 to -3).
+  #[default]
+  HalfUp,
+  /// Round halfway values to the nearest even value (e.g., 2.5 to 2).
+  HalfEven,
+  /// Truncate toward zero (e.g., 2.7 to 2 and -2.7 to -2).
+  TowardZero,
+}
+
+impl FromStr for Rounding {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "half-up" => Ok(Rounding::HalfUp),
+      "half-even" => Ok(Rounding::HalfEven),
+      "toward-zero" => Ok(Rounding::TowardZero),
+      _ => Err(format!("unknown rounding mode [{s}] (expected half-up, half-even, or toward-zero)")),
+    }
+  }
+}
+
+/// Colored output setting.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+  /// Color when output is a terminal and `NO_COLOR` is not set.
+  #[default]
+  Auto,
+  Always,
+  Never,
+}
+
+impl FromStr for ColorMode {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "auto" => Ok(ColorMode::Auto),
+      "always" => Ok(ColorMode::Always),
+      "never" => Ok(ColorMode::Never),
+      _ => Err(format!("unknown color mode [{s}] (expected auto, always, or never)")),
+    }
+  }
+}
+
+/// Interpreter defaults, read from `~/.config/comp/config.toml`.
+///
+/// ```toml
+/// precision = 4               # decimal places shown in output
+/// angle = "deg"               # angle mode (deg or rad)
+/// color = "never"            # colored output (auto, always, or never)
+/// prelude = "~/prelude.cm"    # file of functions loaded at startup
+/// max_ops = 10000000          # maximum operations per evaluation
+/// max_depth = 1000            # maximum depth of user function calls
+/// rational = true             # exact rational arithmetic
+/// integer = true              # 128-bit integer arithmetic
+/// strict = true               # NaN or infinite results are errors
+/// seed = 42                   # random number generator seed
+/// rounding = "half-even"      # rounding mode (half-up, half-even, or toward-zero)
+/// base = "hex"                # integer display base (dec, hex, bin, or oct)
+/// format = "sci"              # number format (auto, fix, sci, eng, or si)
+/// group = ","                 # thousands separator
+/// locale = "de_DE"            # locale of the decimal separator
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+  /// Number of decimal places displayed (full precision if not set).
+  pub precision: Option<usize>,
+  /// Angle mode of the trigonometric commands.
+  pub angle: AngleMode,
+  /// Colored output.
+  pub color: ColorMode,
+  /// Operations file evaluated when the interpreter is constructed.
+  pub prelude: Option<PathBuf>,
+  /// Maximum number of operations processed by a single evaluation (guards
+  /// against runaway recursive functions).
+  pub max_ops: usize,
+  /// Maximum depth of user function calls that have not ended (guards
+  /// against recursive functions without a terminating condition). Calls
+  /// that end the calling function (tail calls) do not add a level. Calls
+  /// nested by commands that take functions (e.g., `map`) are limited to at
+  /// most 256 levels.
+  pub max_depth: usize,
+  /// Exact rational arithmetic (division of integers produces fractions).
+  pub rational: bool,
+  /// Integer arithmetic mode (128-bit integers, with an error on overflow).
+  pub integer: bool,
+  /// Strict mode (commands producing NaN or infinite results raise an
+  /// error).
+  pub strict: bool,
+  /// Seed of the random number generator (random seed if not set).
+  pub seed: Option<u64>,
+  /// Rounding mode of the `round` and `roundn` commands.
+  pub rounding: Rounding,
+  /// Base in which integer values are displayed.
+  pub base: Base,
+  /// Notation in which floating point values are displayed.
+  pub format: Format,
+  /// Separator displayed between groups of thousands (no grouping if not
+  /// set).
+  pub group: Option<char>,
+  /// Locale name (e.g., `de_DE`) determining the decimal separator of input
+  /// and output values (decimal point if not set).
+  pub locale: Option<String>,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Config {
+      precision: None,
+      angle: AngleMode::Radians,
+      color: ColorMode::Auto,
+      prelude: None,
+      max_ops: 10_000_000,
+      max_depth: 10_000,
+      rational: false,
+      integer: false,
+      strict: false,
+      seed: None,
+      rounding: Rounding::HalfUp,
+      base: Base::Dec,
+      format: Format::Auto,
+      group: None,
+      locale: None,
+    }
+  }
+}
+
+impl Config {
+  /// Load the configuration file (built-in defaults if there is none).
+  pub fn load() -> Result<Config, CompError> {
+    match Config::path() {
+      Some(path) if path.exists() => {
+        let contents: String = fs::read_to_string(&path)
+          .map_err(|error| CompError::FileError { path: path.display().to_string(), message: error.to_string() })?;
+
+        Config::parse(&contents)
+          .map_err(|message| CompError::FileError { path: path.display().to_string(), message })
+      },
+      _ => Ok(Config::default()),
+    }
+  }
+
+  /// Parse configuration file contents.
+  pub fn parse(contents: &str) -> Result<Config, String> {
+    let mut config: Config = toml::from_str(contents).map_err(|error| error.message().to_string())?;
+
+    // expand home directory in prelude path
+    if let Some(prelude) = &config.prelude {
+      if let (Ok(rest), Some(home)) = (prelude.strip_prefix("~"), env::var_os("HOME")) {
+        config.prelude = Some(PathBuf::from(home).join(rest));
+      }
+    }
+    config.validate()?;
+
+    Ok(config)
+  }
+
+  /// Check that the settings can be combined (the group separator must
+  /// differ from the decimal separator and cannot be part of a number, so
+  /// that grouped values can be read back).
+  pub fn validate(&self) -> Result<(), String> {
+    match self.group {
+      Some(separator) if separator == self.decimal_separator() => {
+        Err(format!("group separator [{separator}] is the same as the decimal separator"))
+      },
+      Some(separator) if separator.is_ascii_digit() || matches!(separator, '+' | '-' | '\u{2212}') => {
+        Err(format!("group separator [{separator}] is a digit or sign"))
+      },
+      _ => Ok(()),
+    }
+  }
+
+  /// Decimal separator of the configured locale (a comma for languages that
+  /// use a decimal comma).
+  pub fn decimal_separator(&self) -> char {
+    let language: &str = match &self.locale {
+      Some(locale) => locale.split(['_', '-', '.']).next().unwrap_or(""),
+      None => return '.',
+    };
+
+    if DECIMAL_COMMA_LANGUAGES.contains(&language.to_lowercase().as_str()) {
+      ','
+    } else {
+      '.'
+    }
+  }
+
+  // configuration file path ($XDG_CONFIG_HOME/comp/config.toml or
+  // ~/.config/comp/config.toml)
+  fn path() -> Option<PathBuf> {
+    let config_dir: PathBuf = match env::var_os("XDG_CONFIG_HOME") {
+      Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+      _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+
+    Some(config_dir.join("comp").join("config.toml"))
+  }
+}
@@ -0,0 +1,128 @@
+use std::error::Error;
+use std::fmt;
+use crate::token::Span;
+
+/// Error raised while evaluating an operations list.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompError {
+  /// Operation called without enough elements on the stack.
+  StackUnderflow { op: String, depth: usize },
+  /// Operation that pairs stack elements (e.g., `zip+`) called with an odd
+  /// number of elements on stack.
+  UnevenStack { op: String, depth: usize },
+  /// Element popped from an empty stack.
+  EmptyStack,
+  /// Operation that is neither a command, a user-defined function, nor a
+  /// value.
+  UnknownExpression(String),
+  /// Value that cannot be used where a non-negative integer is required.
+  NotAnInteger(String),
+  /// Text value used where a number is required.
+  NotANumber(String),
+  /// Value outside of the domain of an operation (e.g., a Roman numeral
+  /// above 3999).
+  InvalidArgument { op: String, value: String },
+  /// Value without a multiplicative inverse modulo the given modulus.
+  NoInverse { value: String, modulus: String },
+  /// Function definition missing a name or a terminating `end`.
+  IncompleteFunction(String),
+  /// Quotation missing a terminating `]`.
+  IncompleteQuotation,
+  /// Operation called without its required argument (e.g., a file path).
+  MissingArgument(String),
+  /// File that could not be read, written, or parsed.
+  FileError { path: String, message: String },
+  /// Evaluation exceeded the maximum number of operations.
+  StepLimit(usize),
+  /// Integer mode result outside of the 128-bit integer range (or
+  /// undefined, e.g., division by zero).
+  IntegerOverflow,
+  /// Function argument that is neither a command nor a user-defined
+  /// function.
+  UnknownFunction(String),
+  /// Function calls (e.g., of `map`, or recursive user function calls)
+  /// nested deeper than the given number of levels.
+  NestingLimit(usize),
+  /// Operation that repeats or reverts a previous operation called before
+  /// any operation.
+  NoHistory(String),
+  /// Variable recalled before a value is stored in it.
+  UnknownVariable(String),
+  /// Strict mode result that is NaN or infinite.
+  NonFinite(String),
+  /// Settings that cannot be combined (e.g., a group separator equal to the
+  /// decimal separator).
+  InvalidConfig(String),
+  /// Error raised by the operation at the given source location.
+  Located { span: Span, error: Box<CompError> },
+}
+
+impl CompError {
+  // attach operation source location to error
+  pub(crate) fn at(self, span: &Span) -> CompError {
+    match self {
+      CompError::Located { .. } => self,
+      error => CompError::Located { span: span.clone(), error: Box::new(error) },
+    }
+  }
+}
+
+impl fmt::Display for CompError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      CompError::StackUnderflow { op, depth } => {
+        write!(f, "[{op}] operation called without at least {depth} element(s) on stack")
+      },
+      CompError::UnevenStack { op, depth } => {
+        write!(f, "[{op}] operation requires an even number of elements on stack ({depth} found)")
+      },
+      CompError::EmptyStack => write!(f, "element popped from empty stack"),
+      CompError::UnknownExpression(op) => {
+        write!(f, "unknown expression [{op}] is not a recognized operation or value")
+      },
+      CompError::NotAnInteger(value) => {
+        write!(f, "value [{value}] is not a non-negative integer")
+      },
+      CompError::NotANumber(value) => write!(f, "value [{value}] is not a number"),
+      CompError::InvalidArgument { op, value } => {
+        write!(f, "value [{value}] is not a valid argument of the [{op}] operation")
+      },
+      CompError::NoInverse { value, modulus } => {
+        write!(f, "value [{value}] has no inverse modulo [{modulus}] (not coprime)")
+      },
+      CompError::IncompleteFunction(name) => {
+        write!(f, "function definition [{name}] is missing a terminating [end]")
+      },
+      CompError::IncompleteQuotation => write!(f, "quotation is missing a terminating []]"),
+      CompError::MissingArgument(op) => {
+        write!(f, "[{op}] operation called without an argument")
+      },
+      CompError::FileError { path, message } => {
+        write!(f, "could not access file [{path}]: {message}")
+      },
+      CompError::StepLimit(max_ops) => {
+        write!(f, "evaluation exceeded the maximum of {max_ops} operations")
+      },
+      CompError::IntegerOverflow => write!(f, "integer result is undefined or out of the 128-bit range"),
+      CompError::UnknownFunction(name) => {
+        write!(f, "[{name}] is not a command or user-defined function")
+      },
+      CompError::NestingLimit(depth) => {
+        write!(f, "function calls nested deeper than {depth} levels")
+      },
+      CompError::NoHistory(op) => {
+        write!(f, "[{op}] operation called without a previous operation")
+      },
+      CompError::UnknownVariable(name) => {
+        write!(f, "variable [{name}] is not defined (no value stored)")
+      },
+      CompError::NonFinite(op) => {
+        write!(f, "[{op}] operation produced a non-finite result (NaN or infinity)")
+      },
+      CompError::InvalidConfig(message) => write!(f, "invalid configuration: {message}"),
+      CompError::Located { span, error } => write!(f, "{error} ({span})"),
+    }
+  }
+}
+
+impl Error for CompError {}
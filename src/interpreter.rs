@@ -0,0 +1,4375 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use colored::*;
+use std::fs;
+use std::path::Path;
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{FromPrimitive, One, Signed, ToPrimitive, Zero};
+use serde::{Deserialize, Serialize};
+use crate::config::AngleMode;
+use crate::config::Base;
+use crate::config::Config;
+use crate::config::Format;
+use crate::config::Rounding;
+use crate::error::CompError;
+use crate::random::Rng;
+use crate::session::Session;
+use crate::session::read_stack;
+use crate::session::write_stack;
+use crate::token::Span;
+use crate::token::Token;
+use crate::value::format_complex;
+use crate::value::Value;
+
+/*
+
+    note: base data structure is a vector (linked
+    list) used as a stack. atoms on the list are
+    either be symbols (commands) or values. each
+    calculation is a list of operations that are
+    processed in order of occurrence. this is an
+    implementation of a lisp interpreter for rev-
+    erse polish notation s-expressions (sexp).
+
+      operations list structure
+        (object : command or value)
+        "5"
+        "sqrt"
+        "1
+        "-"
+        "2"
+        "/"
+
+    a list evaluation engine takes the list of
+    strings and executes the corresponding oper-
+    ations then returns the resulting mutated
+    stack.
+
+*/
+
+
+// stack and memory registers before an operation (restored by undo). only
+// the top of the stack that the operation can change is saved (above the
+// given number of unchanged elements), and the variables that the operation
+// stores
+struct Snapshot {
+  base: usize,
+  top: Vec<Value>,
+  mem_a: Value,
+  mem_b: Value,
+  mem_c: Value,
+  vars: Vec<(String, Option<Value>)>, // previous values (None if undefined)
+}
+
+// local variables of a user function call, which ends once the pending
+// operations are reduced to those that followed the call
+struct Frame {
+  nesting: usize,
+  end: usize,
+  vars: HashMap<String, Value>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Function {
+  name: String,
+  #[serde(default)] // functions saved before declared parameters
+  params: Vec<String>,
+  fops: Vec<Token>,
+}
+
+/// Documentation of a native command.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CommandInfo {
+  /// Stack effect (stack elements consumed -- stack elements produced).
+  pub effect: &'static str,
+  pub description: &'static str,
+  /// Example operations list and resulting stack (`3 4 + => 7`).
+  pub example: &'static str,
+}
+
+impl CommandInfo {
+  /// Number of stack elements consumed (`None` if the command consumes a
+  /// variable number of elements).
+  pub fn arity(&self) -> Option<usize> {
+    let inputs: &str = self.effect.split("--").next().unwrap_or("");
+
+    if inputs.contains("...") {
+      None
+    } else {
+      Some(inputs.split_whitespace().count())
+    }
+  }
+
+  // stack depth after the command is called with the given depth (`None` if
+  // the resulting depth is unknown), or the required depth if the stack is
+  // too shallow
+  fn depth_after(&self, depth: usize) -> Result<Option<usize>, usize> {
+    let (inputs, outputs) = self.effect.split_once("--").unwrap_or((self.effect, ""));
+    let count = |elements: &str| elements.split_whitespace().filter(|element| *element != "...").count();
+    let consumed: usize = count(inputs);
+    let produced: usize = count(outputs);
+
+    match (inputs.contains("..."), outputs.contains("...")) {
+      _ if depth < consumed => Err(consumed),
+      (true, false) => Ok(Some(produced)), // consumes the whole stack
+      (false, true) => Ok(None),
+      _ => Ok(Some(depth - consumed + produced)),
+    }
+  }
+}
+
+// native command (function and documentation)
+#[derive(Clone, Copy)]
+struct Native {
+  func: Command,
+  info: CommandInfo,
+}
+
+/// Reverse Polish notation command interpreter.
+///
+/// Operations are queued on `ops` and evaluated in order of occurrence by
+/// [`Interpreter::process_ops`]. Values are pushed onto `stack`, and commands
+/// consume and produce stack elements.
+pub struct Interpreter {
+  /// Computation stack (the last element is the top of the stack).
+  pub stack: Vec<Value>,
+  mem_a: Value,
+  mem_b: Value,
+  mem_c: Value,
+  vars: HashMap<String, Value>, // named variables
+  frames: Vec<Frame>, // local variables of active user function calls
+  includes: Vec<(String, usize)>, // included files (and the operations following them)
+  /// Pending operations list.
+  pub ops: VecDeque<Token>,
+  /// Lines printed by the `print` and `pstack` commands, in order (the
+  /// caller displays and drains them).
+  pub output: Vec<String>,
+  fns: Vec<Function>,
+  cmap: HashMap<String, Native>,
+  steps: usize, // operations processed in current evaluation
+  nesting: usize, // depth of nested function calls (e.g., of map)
+  last_op: Option<(String, Vec<Value>)>, // last command and its repeated operands
+  outer_ops: Option<usize>, // pending operations following a function call
+  history: VecDeque<Snapshot>, // states before the most recent operations
+  recording: bool, // changes of the current operation are saved in history
+  rng: Rng,
+  /// Interpreter settings.
+  pub config: Config,
+}
+
+// largest exact integer result (bits) and factorial argument computed before
+// falling back to floating point
+const MAX_EXACT_BITS: u64 = 1 << 20;
+const MAX_EXACT_FACTORIAL: u64 = 10_000;
+
+// commands followed by function arguments (e.g., map <fn>) and the number of
+// function arguments
+const FUNCTION_COMMANDS: [(&str, usize); 11] = [
+  ("map", 1), ("filter", 1), ("fold", 1), ("reduce", 1), ("zipwith", 1), ("keep", 1), ("if", 1), ("ifelse", 2),
+  ("while", 2), ("times", 1), ("for", 1),
+];
+
+// maximum number of operations that can be undone
+const MAX_UNDO: usize = 100;
+
+// maximum depth of nested function calls (e.g., a function that maps itself),
+// which also caps the configured maximum depth of user function calls
+const MAX_NESTING: usize = 256;
+
+// SI prefixes of engineering notation exponents
+const SI_PREFIXES: [(i32, &str); 17] = [
+  (-24, "y"), (-21, "z"), (-18, "a"), (-15, "f"), (-12, "p"), (-9, "n"), (-6, "u"), (-3, "m"), (0, ""),
+  (3, "k"), (6, "M"), (9, "G"), (12, "T"), (15, "P"), (18, "E"), (21, "Z"), (24, "Y"),
+];
+
+// Lanczos approximation coefficients of the gamma function (g = 7, n = 9)
+const LANCZOS_G: f64 = 7.0;
+
+// largest argument of the gamma function with a finite result
+const MAX_GAMMA: f64 = 171.624_376_956_302_7;
+const LANCZOS: [f64; 9] = [
+  0.999_999_999_999_809_9, 676.520_368_121_885_1, -1_259.139_216_722_402_8,
+  771.323_428_777_653_1, -176.615_029_162_140_6, 12.507_343_278_686_905,
+  -0.138_571_095_265_720_12, 9.984_369_578_019_572e-6, 1.505_632_735_149_311_6e-7,
+];
+
+// Miller-Rabin witnesses (deterministic below 3.3e24)
+const PRIME_WITNESSES: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+// Roman numeral symbols (including subtractive pairs) in descending order
+const ROMAN_NUMERALS: [(u32, &str); 13] = [
+  (1000, "M"), (900, "CM"), (500, "D"), (400, "CD"), (100, "C"), (90, "XC"), (50, "L"),
+  (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I"),
+];
+
+// largest number of displayed decimal places
+const MAX_PRECISION: usize = 1_000;
+
+// standard user functions defined at startup
+const PRELUDE: &str = include_str!("prelude.comp");
+
+// native command function
+type Command = fn(&mut Interpreter, &str) -> Result<(), CompError>;
+
+impl Interpreter {
+  /// Construct an interpreter with an empty stack and the native command set,
+  /// using the defaults from the user configuration file. The built-in
+  /// defaults are used if the configuration file or prelude cannot be loaded.
+  pub fn new() -> Interpreter {
+    Config::load()
+      .and_then(Interpreter::with_config)
+      .unwrap_or_else(|_| Interpreter::construct(Config::default()))
+  }
+
+  /// Construct an interpreter using the given settings, evaluating the
+  /// configured prelude file. Settings that cannot be combined are an
+  /// error.
+  pub fn with_config(config: Config) -> Result<Interpreter, CompError> {
+    config.validate().map_err(CompError::InvalidConfig)?;
+    let prelude: Option<std::path::PathBuf> = config.prelude.clone();
+    let mut cint = Interpreter::construct(config);
+
+    if let Some(path) = prelude {
+      let contents: String = fs::read_to_string(&path)
+        .map_err(|error| CompError::FileError { path: path.display().to_string(), message: error.to_string() })?;
+      cint.push_file_ops(&contents, &path.display().to_string());
+      cint.process_ops()?;
+    }
+
+    Ok(cint)
+  }
+
+  // constructor
+  fn construct(config: Config) -> Interpreter {
+    let mut cint = Interpreter {
+      stack: Vec::new(),
+      mem_a: Value::Float(0.0),
+      mem_b: Value::Float(0.0),
+      mem_c: Value::Float(0.0),
+      vars: HashMap::new(),
+      frames: Vec::new(),
+      includes: Vec::new(),
+      ops: VecDeque::new(),
+      output: Vec::new(),
+      fns: Vec::new(),
+      cmap: HashMap::new(),
+      steps: 0,
+      nesting: 0,
+      last_op: None,
+      outer_ops: None,
+      history: VecDeque::new(),
+      recording: false,
+      rng: config.seed.map_or_else(Rng::from_entropy, Rng::new),
+      config,
+    };
+    cint.init();
+
+    // standard functions are defined like user functions (and can be
+    // redefined), but cannot be undone
+    cint.push_ops(PRELUDE);
+    if cint.process_ops().is_err() {
+      cint.fns.clear();
+    }
+    cint.history.clear();
+    cint.last_op = None;
+
+    cint
+  }
+
+  /// Split whitespace-separated operations and add them to the end of the
+  /// operations list.
+  pub fn push_ops(&mut self, contents: &str) {
+    self.ops.extend(Token::tokenize(contents, None));
+  }
+
+  /// Split whitespace-separated operations read from a source file and add
+  /// them to the end of the operations list.
+  pub fn push_file_ops(&mut self, contents: &str, source: &str) {
+    self.ops.extend(Token::tokenize(contents, Some(source)));
+  }
+
+  /// Add a list of operations (e.g., command line arguments) to the end of
+  /// the operations list.
+  pub fn push_args(&mut self, args: &[String]) {
+    for (index, arg) in args.iter().enumerate() {
+      self.ops.push_back(Token {
+        text: arg.to_string(),
+        span: Span { index: index + 1, source: None, line: None },
+      });
+    }
+  }
+
+  /// Names of the native commands (sorted).
+  pub fn commands(&self) -> Vec<&str> {
+    let mut names: Vec<&str> = self.cmap.keys().map(|name| name.as_str()).collect();
+    names.sort_unstable();
+
+    names
+  }
+
+  /// Documentation of a native command (`None` if there is no native
+  /// command with the given name).
+  pub fn describe(&self, name: &str) -> Option<CommandInfo> {
+    self.cmap.get(name).map(|native| native.info)
+  }
+
+  /// Memory register values (`a`, `b`, and `c`).
+  pub fn registers(&self) -> [(&str, &Value); 3] {
+    [("a", &self.mem_a), ("b", &self.mem_b), ("c", &self.mem_c)]
+  }
+
+  /// Named variables and their values (sorted by name).
+  pub fn variables(&self) -> Vec<(&str, &Value)> {
+    let mut vars: Vec<(&str, &Value)> = self.vars.iter().map(|(name, value)| (name.as_str(), value)).collect();
+    vars.sort_unstable_by_key(|(name, _)| *name);
+
+    vars
+  }
+
+  /// Whether a user function with the given name is defined.
+  pub fn is_function(&self, name: &str) -> bool {
+    self.is_user_function(name).is_some()
+  }
+
+  /// Format a stack value for display using the configured number format,
+  /// precision, and locale (and base for integer values).
+  pub fn format_value(&self, value: &Value) -> String {
+    if let Value::Str(text) = value {
+      return text.clone();
+    }
+    let formatted: String = self.format_number(value);
+
+    // swap decimal point and comma group separator for a decimal comma locale
+    match self.config.decimal_separator() {
+      ',' => formatted.chars().map(|c| match c {
+        '.' => ',',
+        ',' => '.',
+        _ => c,
+      }).collect(),
+      _ => formatted,
+    }
+  }
+
+  // parse value using the decimal separator of the configured locale
+  fn parse_value(&self, op: &str) -> Option<Value> {
+    match self.config.decimal_separator() {
+      ',' if op.contains(',') => Value::parse(&op.replace(',', ".")),
+      _ => Value::parse(op),
+    }
+  }
+
+  // format stack value (decimal point)
+  fn format_number(&self, value: &Value) -> String {
+    match value {
+      Value::Int(_) | Value::Big(_) if self.config.base != Base::Dec => {
+        let i: BigInt = value.to_bigint().unwrap_or_default();
+        let sign: &str = if i.is_negative() { "-" } else { "" };
+        match self.config.base {
+          Base::Hex => format!("{sign}0x{:x}", i.magnitude()),
+          Base::Bin => format!("{sign}0b{:b}", i.magnitude()),
+          Base::Oct => format!("{sign}0o{:o}", i.magnitude()),
+          Base::Dec => i.to_string(),
+        }
+      },
+      Value::Int(_) | Value::Big(_) if matches!(self.config.format, Format::Sci | Format::Eng | Format::Si) => {
+        self.format_float(value.to_f64())
+      },
+      Value::Int(_) | Value::Big(_) => self.group_digits(value.to_string()),
+      Value::Float(f) => self.format_float(*f),
+      Value::Complex(z) => format_complex(z, |x| self.format_float(x)),
+      _ => value.to_string(),
+    }
+  }
+
+  // insert the configured group separator between thousands of the integer
+  // part of a number in fixed notation
+  fn group_digits(&self, formatted: String) -> String {
+    // the decimal point and comma are swapped for display in a decimal comma
+    // locale (see format_value), so the separator is swapped in advance
+    let separator: char = match (self.config.group, self.config.decimal_separator()) {
+      (Some('.'), ',') => ',',
+      (Some(','), ',') => '.',
+      (Some(separator), _) => separator,
+      (None, _) => return formatted,
+    };
+    let unsigned: &str = formatted.trim_start_matches('-');
+    let (integer, fraction) = unsigned.split_at(unsigned.find('.').unwrap_or(unsigned.len()));
+    if !integer.chars().all(|c| c.is_ascii_digit()) || !fraction.chars().skip(1).all(|c| c.is_ascii_digit()) {
+      return formatted; // scientific notation or non-finite number
+    }
+
+    let mut grouped: String = String::new();
+    for (i, digit) in integer.chars().enumerate() {
+      if i > 0 && (integer.len() - i) % 3 == 0 {
+        grouped.push(separator);
+      }
+      grouped.push(digit);
+    }
+    let sign: &str = &formatted[..formatted.len() - unsigned.len()];
+
+    format!("{sign}{grouped}{fraction}")
+  }
+
+  // format floating point number using the configured number format and
+  // precision (automatic format trims trailing zeros)
+  fn format_float(&self, f: f64) -> String {
+    if !f.is_finite() {
+      return f.to_string();
+    }
+    let large: bool = f != 0.0 && (f.abs() >= 1e16 || f.abs() < 1e-6);
+
+    let formatted: String = match (self.config.format, self.config.precision) {
+      (Format::Sci, Some(precision)) => format!("{f:.precision$e}"),
+      (Format::Sci, None) => format!("{f:e}"),
+      (Format::Fix, Some(precision)) => format!("{f:.precision$}"),
+      (Format::Fix, None) => f.to_string(),
+      (Format::Eng, _) => {
+        let (mantissa, exponent) = Interpreter::engineering(f, self.config.precision);
+        format!("{mantissa}e{exponent}")
+      },
+      (Format::Si, _) => {
+        let (mantissa, exponent) = Interpreter::engineering(f, self.config.precision);
+        match SI_PREFIXES.iter().find(|(e, _)| *e == exponent) {
+          Some((_, prefix)) => format!("{mantissa}{prefix}"),
+          None => format!("{mantissa}e{exponent}"), // beyond the SI prefixes
+        }
+      },
+      (Format::Auto, Some(precision)) if large => Interpreter::trim_zeros(&format!("{f:.precision$e}")),
+      (Format::Auto, None) if large => format!("{f:e}"),
+      (Format::Auto, Some(precision)) => Interpreter::trim_zeros(&format!("{f:.precision$}")),
+      (Format::Auto, None) => f.to_string(),
+    };
+
+    // negative values rounded to zero are displayed without a sign
+    match formatted.strip_prefix('-') {
+      Some(rest) if rest.chars().all(|c| c == '0' || c == '.') => rest.to_string(),
+      _ => self.group_digits(formatted),
+    }
+  }
+
+  // engineering notation mantissa and exponent (multiple of three) of a
+  // finite number, with the given number of mantissa decimals (shortest
+  // representation if not set)
+  fn engineering(f: f64, precision: Option<usize>) -> (String, i32) {
+    let sign: &str = if f.is_sign_negative() && f != 0.0 { "-" } else { "" };
+    let sci: String = format!("{:e}", f.abs());
+    let (digits, exponent) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    let shift: i32 = exponent.rem_euclid(3);
+
+    if let Some(precision) = precision {
+      let mut exponent: i32 = exponent - shift;
+      let mut mantissa: f64 = f.abs() / 10f64.powi(exponent);
+      if format!("{mantissa:.precision$}").parse::<f64>().unwrap_or(0.0) >= 1000.0 {
+        mantissa /= 1000.0; // rounded up to the next multiple of three
+        exponent += 3;
+      }
+      return (format!("{sign}{mantissa:.precision$}"), exponent);
+    }
+
+    // move the decimal point of the shortest representation
+    let mut digits: String = digits.replace('.', "");
+    let point: usize = 1 + shift as usize;
+    while digits.len() < point {
+      digits.push('0');
+    }
+    let mantissa: String = match &digits[point..] {
+      "" => digits[..point].to_string(),
+      fraction => format!("{}.{fraction}", &digits[..point]),
+    };
+
+    (format!("{sign}{mantissa}"), exponent - shift)
+  }
+
+  // remove trailing zeros from the fractional part of a formatted number
+  // (e.g., 1.2500 or 1.2500e3)
+  fn trim_zeros(formatted: &str) -> String {
+    let (mantissa, exponent) = match formatted.split_once('e') {
+      Some((mantissa, exponent)) => (mantissa, format!("e{exponent}")),
+      None => (formatted, String::new()),
+    };
+    let trimmed: &str = if mantissa.contains('.') {
+      mantissa.trim_end_matches('0').trim_end_matches('.')
+    } else {
+      mantissa
+    };
+
+    format!("{trimmed}{exponent}")
+  }
+
+  /// Evaluate all pending operations, leaving the results on the stack.
+  ///
+  /// Evaluation stops at the first failing operation, and the remaining
+  /// operations are discarded. The returned error is located at the source
+  /// of the failing operation. Evaluation is aborted after the configured
+  /// maximum number of operations.
+  pub fn process_ops(&mut self) -> Result<(), CompError> {
+    while self.step()?.is_some() {}
+
+    Ok(())
+  }
+
+  /// Evaluate the next pending operation, returning the processed operation
+  /// (`None` if there are no pending operations).
+  ///
+  /// Errors are handled as in [`Interpreter::process_ops`]. The operation
+  /// count of an evaluation is reset once all pending operations have been
+  /// processed.
+  pub fn step(&mut self) -> Result<Option<Token>, CompError> {
+    // operations of a called user function precede the outer operations
+    let in_function: bool = self.outer_ops.is_some_and(|n| self.ops.len() > n);
+    self.close_frames();
+    let token: Token = match self.ops.pop_front() { // pop first operation
+      Some(token) => token,
+      None => return Ok(None),
+    };
+    if !in_function {
+      self.outer_ops = None;
+      self.remember(&token.text);
+      self.snapshot(&token.text);
+    } else {
+      self.extend_snapshot(&token.text);
+    }
+
+    if let Err(error) = self.eval_token(&token) {
+      self.ops.clear();
+      self.frames.clear();
+      self.includes.clear();
+      self.steps = 0;
+      self.outer_ops = None;
+      self.recording = false;
+      return Err(error);
+    }
+
+    if self.ops.is_empty() {
+      self.frames.clear();
+      self.includes.clear();
+      self.steps = 0;
+      self.outer_ops = None;
+      self.recording = false;
+    }
+
+    Ok(Some(token))
+  }
+
+  // remember command or user function for again (commands are repeated with
+  // all but their first operand, so that e.g. 2 x again multiplies by 2 again)
+  fn remember(&mut self, op: &str) {
+    if !Interpreter::is_repeatable(op) {
+      return;
+    }
+
+    if let Some(native) = self.cmap.get(op) {
+      let operands: Vec<Value> = match native.info.arity() {
+        Some(arity) if arity >= 2 && self.stack.len() >= arity => self.stack[self.stack.len() + 1 - arity..].to_vec(),
+        _ => Vec::new(),
+      };
+      self.last_op = Some((op.to_string(), operands));
+    } else if self.is_user_function(op).is_some() {
+      self.last_op = Some((op.to_string(), Vec::new()));
+    }
+  }
+
+  // save state before operation for undo (user functions are undone as a
+  // whole)
+  fn snapshot(&mut self, op: &str) {
+    if op == "undo" {
+      return;
+    }
+
+    let base: usize = self.touched_base(op);
+    if self.history.len() == MAX_UNDO {
+      self.history.pop_front();
+    }
+    self.history.push_back(Snapshot {
+      base,
+      top: self.stack[base..].to_vec(),
+      mem_a: self.mem_a.clone(),
+      mem_b: self.mem_b.clone(),
+      mem_c: self.mem_c.clone(),
+      vars: Vec::new(),
+    });
+    self.recording = true;
+  }
+
+  // extend the snapshot of a user function call to the elements that an
+  // operation of the function can change (the elements below the saved top of
+  // the stack are unchanged until then)
+  fn extend_snapshot(&mut self, op: &str) {
+    if !self.recording {
+      return;
+    }
+
+    let base: usize = self.touched_base(op);
+    if let Some(snapshot) = self.history.back_mut() {
+      if base < snapshot.base && snapshot.base <= self.stack.len() {
+        let mut top: Vec<Value> = self.stack[base..snapshot.base].to_vec();
+        top.append(&mut snapshot.top);
+        snapshot.top = top;
+        snapshot.base = base;
+      }
+    }
+  }
+
+  // lowest stack position that an operation can change (commands with a
+  // fixed number of operands only change the top of the stack unless they
+  // call functions, user functions start by binding their parameters, and
+  // values are pushed onto the stack)
+  fn touched_base(&self, op: &str) -> usize {
+    let depth: usize = self.stack.len();
+    if self.frames.last().is_some_and(|frame| frame.vars.contains_key(op)) {
+      return depth;
+    }
+
+    match self.cmap.get(op) {
+      Some(_) if Interpreter::function_arguments(op) > 0 => 0,
+      Some(native) => native.info.arity().map_or(0, |arity| depth.saturating_sub(arity)),
+      None => match self.is_user_function(op) {
+        Some(index) => depth.saturating_sub(self.fns[index].params.len()),
+        None => depth,
+      },
+    }
+  }
+
+  // save the value of a global variable before the current operation stores
+  // it (once per operation)
+  fn save_variable(&mut self, name: &str) {
+    if !self.recording {
+      return;
+    }
+
+    if let Some(snapshot) = self.history.back_mut() {
+      if !snapshot.vars.iter().any(|(saved, _)| saved == name) {
+        snapshot.vars.push((name.to_string(), self.vars.get(name).cloned()));
+      }
+    }
+  }
+
+  // process operation, counting it toward the maximum number of operations
+  fn eval_token(&mut self, token: &Token) -> Result<(), CompError> {
+    self.steps += 1;
+    let result: Result<(), CompError> = if self.steps > self.config.max_ops {
+      Err(CompError::StepLimit(self.config.max_ops))
+    } else {
+      self.process_node(&token.text)
+    };
+
+    result.map_err(|error| error.at(&token.span))
+  }
+
+  /// Save the stack, memory registers, variables, and user-defined functions
+  /// to a file.
+  pub fn save_session(&self, path: &str) -> Result<(), CompError> {
+    let session: Session = Session {
+      stack: self.stack.clone(),
+      mem_a: self.mem_a.clone(),
+      mem_b: self.mem_b.clone(),
+      mem_c: self.mem_c.clone(),
+      vars: self.vars.clone(),
+      fns: self.fns.clone(),
+    };
+
+    session.write(path)
+  }
+
+  /// Restore the stack, memory registers, variables, and user-defined
+  /// functions from a file written by [`Interpreter::save_session`].
+  pub fn load_session(&mut self, path: &str) -> Result<(), CompError> {
+    let session: Session = Session::read(path)?;
+
+    self.stack = session.stack;
+    self.mem_a = session.mem_a;
+    self.mem_b = session.mem_b;
+    self.mem_c = session.mem_c;
+    let names: Vec<String> = self.vars.keys().chain(session.vars.keys()).cloned().collect();
+    for name in &names {
+      self.save_variable(name);
+    }
+    self.vars = session.vars;
+    self.fns = session.fns;
+
+    Ok(())
+  }
+
+  /// Check the pending operations without evaluating them, returning the
+  /// problems found (unknown operations, incomplete function definitions,
+  /// missing arguments, and stack underflows).
+  ///
+  /// Stack depth is simulated from the current stack using the stack effects
+  /// of the native commands. Each problem is located at its operation.
+  pub fn check(&self) -> Vec<CompError> {
+    let tokens: Vec<&Token> = self.ops.iter().collect();
+    let mut errors: Vec<CompError> = Vec::new();
+
+    // collect user function definitions (existing and pending) with their
+    // declared parameters
+    let mut fns: HashMap<&str, (Vec<&str>, Vec<&Token>)> = HashMap::new();
+    for function in &self.fns {
+      let params: Vec<&str> = function.params.iter().map(|param| param.as_str()).collect();
+      fns.insert(&function.name, (params, function.fops.iter().collect()));
+    }
+    let mut ops: Vec<&Token> = Vec::new(); // operations outside of definitions
+    let mut bodies: Vec<(Vec<&str>, Vec<&Token>)> = Vec::new(); // pending definitions
+    let mut i: usize = 0;
+    while i < tokens.len() {
+      match &tokens[i].text[..] {
+        "(" => i = Interpreter::skip_comment(&tokens, i),
+        "fn" => {
+          let name: &Token = match tokens.get(i + 1) {
+            Some(name) => name,
+            None => {
+              errors.push(CompError::IncompleteFunction(String::new()).at(&tokens[i].span));
+              break;
+            },
+          };
+          let end: usize = match tokens[i + 2..].iter().position(|token| token.text == "end") {
+            Some(offset) => i + 2 + offset,
+            None => {
+              errors.push(CompError::IncompleteFunction(name.text.clone()).at(&tokens[i].span));
+              break;
+            },
+          };
+          let (params, body) = Interpreter::split_params(&tokens[i + 2..end]);
+          fns.insert(&name.text, (params.clone(), body.clone()));
+          bodies.push((params, body));
+          i = end + 1;
+        },
+        "alias" => {
+          // an alias is checked as a function calling the existing command
+          // (unknown commands are reported once at the alias)
+          if let (Some(name), Some(existing)) = (tokens.get(i + 1), tokens.get(i + 2)) {
+            let known: bool = self.cmap.contains_key(&existing.text) || fns.contains_key(&existing.text[..]);
+            fns.insert(&name.text, (Vec::new(), if known { vec![existing] } else { Vec::new() }));
+          }
+          ops.extend(tokens[i..tokens.len().min(i + 3)].iter());
+          i += 3;
+        },
+        _ => {
+          ops.push(tokens[i]);
+          i += 1;
+        },
+      }
+    }
+
+    // unknown operations in function definitions (parameters and local
+    // variables are known names)
+    for (mut names, body) in bodies {
+      let mut i: usize = 0;
+      while i < body.len() {
+        match &body[i].text[..] {
+          "(" => i = Interpreter::skip_comment(&body, i),
+          "local" => {
+            names.extend(body.get(i + 1).map(|name| name.text.as_str()));
+            i += 2;
+          },
+          "store" | "recall" | "save" | "load" | "wstack" | "rstack" | "include" => i += 2, // skip argument
+          "alias" => i += 3, // skip names
+          text => {
+            if !names.contains(&text) && text != "]" && !self.cmap.contains_key(text) && !fns.contains_key(text) && self.parse_value(text).is_none() {
+              errors.push(CompError::UnknownExpression(text.to_string()).at(&body[i].span));
+            }
+            i += 1;
+          },
+        }
+      }
+    }
+
+    // simulate stack depth of operations
+    self.check_ops(&ops, Some(self.stack.len()), &fns, &[], &mut Vec::new(), &mut errors);
+
+    errors
+  }
+
+  // declared parameters (preceding |) and operations of a function
+  // definition
+  fn split_params<'a>(body: &[&'a Token]) -> (Vec<&'a str>, Vec<&'a Token>) {
+    match body.iter().position(|token| token.text == "|") {
+      Some(bar) => (body[..bar].iter().map(|token| token.text.as_str()).collect(), body[bar + 1..].to_vec()),
+      None => (Vec::new(), body.to_vec()),
+    }
+  }
+
+  // simulate stack depth of an operations list (of a user function with the
+  // given parameters), returning the resulting depth (`None` once the depth is
+  // unknown)
+  fn check_ops<'a>(&self, ops: &[&'a Token], mut depth: Option<usize>, fns: &HashMap<&'a str, (Vec<&'a str>, Vec<&'a Token>)>,
+                   params: &[&'a str], calls: &mut Vec<&'a str>, errors: &mut Vec<CompError>) -> Option<usize> {
+    // report each problem once (function bodies may be checked repeatedly)
+    fn report(errors: &mut Vec<CompError>, error: CompError) {
+      if !errors.contains(&error) {
+        errors.push(error);
+      }
+    }
+
+    let mut names: Vec<&str> = params.to_vec(); // parameters and local variables
+    let mut i: usize = 0;
+    while i < ops.len() {
+      let token: &Token = ops[i];
+      match &token.text[..] {
+        "(" => {
+          i = Interpreter::skip_comment(ops, i);
+          continue;
+        },
+        text if names.contains(&text) => depth = depth.map(|n| n + 1),
+        "[" => {
+          i = self.check_quotation(ops, i, fns, &names, calls, errors);
+          depth = depth.map(|n| n + 1);
+          continue;
+        },
+        "call" => depth = None, // stack depth depends on the quotation
+        "save" | "load" | "wstack" | "rstack" | "include" | "store" | "local" | "recall" if i + 1 == ops.len() => {
+          report(errors, CompError::MissingArgument(token.text.clone()).at(&token.span));
+        },
+        "save" | "load" | "wstack" => i += 1, // skip file argument
+        "alias" => {
+          match ops.get(i + 2) {
+            Some(existing) if !self.cmap.contains_key(&existing.text) && !fns.contains_key(&existing.text[..]) => {
+              report(errors, CompError::UnknownFunction(existing.text.clone()).at(&existing.span));
+            },
+            Some(_) => (),
+            None => report(errors, CompError::MissingArgument(token.text.clone()).at(&token.span)),
+          }
+          i += 2; // skip names
+        },
+        "store" | "local" => { // skip variable name
+          if token.text == "local" && !calls.is_empty() {
+            names.push(&ops[i + 1].text);
+          }
+          depth = match depth {
+            Some(0) => {
+              report(errors, CompError::StackUnderflow { op: token.text.clone(), depth: 1 }.at(&token.span));
+              None
+            },
+            depth => depth.map(|n| n - 1),
+          };
+          i += 1;
+        },
+        "recall" => {
+          depth = depth.map(|n| n + 1);
+          i += 1;
+        },
+        "rstack" | "include" => {
+          depth = None; // stack depth depends on the file
+          i += 1;
+        },
+        text if Interpreter::function_arguments(text) > 0 => {
+          let mut next: usize = i + 1; // position of the next function argument
+          for _ in 0..Interpreter::function_arguments(text) {
+            match ops.get(next) {
+              Some(fop) if fop.text == "[" => next = self.check_quotation(ops, next, fns, &names, calls, errors),
+              Some(name) if !self.cmap.contains_key(&name.text) && !fns.contains_key(&name.text[..]) => {
+                report(errors, CompError::UnknownFunction(name.text.clone()).at(&name.span));
+                next += 1;
+              },
+              Some(_) => next += 1,
+              None => {
+                report(errors, CompError::MissingArgument(token.text.clone()).at(&token.span));
+                break;
+              },
+            }
+          }
+          depth = None; // stack depth depends on the function
+          i = next - 1; // skip function arguments
+        },
+        text if self.cmap.contains_key(text) => {
+          let info: CommandInfo = self.cmap[text].info;
+          depth = match depth.map(|n| info.depth_after(n)) {
+            Some(Ok(after)) => after,
+            Some(Err(required)) => {
+              report(errors, CompError::StackUnderflow { op: token.text.clone(), depth: required }.at(&token.span));
+              None
+            },
+            None => None,
+          };
+        },
+        text if fns.contains_key(text) => {
+          let (params, body) = &fns[text];
+          depth = match depth {
+            Some(n) if n < params.len() => {
+              report(errors, CompError::StackUnderflow { op: token.text.clone(), depth: params.len() }.at(&token.span));
+              None
+            },
+            depth => depth.map(|n| n - params.len()),
+          };
+          depth = if calls.contains(&text) { // recursive call (unknown depth)
+            None
+          } else {
+            calls.push(fns.get_key_value(text).unwrap().0);
+            let after: Option<usize> = self.check_ops(body, depth, fns, params, calls, errors);
+            calls.pop();
+            after
+          };
+        },
+        text => match self.parse_value(text) {
+          Some(_) => depth = depth.map(|n| n + 1),
+          None => {
+            report(errors, CompError::UnknownExpression(text.to_string()).at(&token.span));
+            depth = None;
+          },
+        },
+      }
+      i += 1;
+    }
+
+    depth
+  }
+
+  // position following the end of the comment starting at the given position
+  fn skip_comment(tokens: &[&Token], start: usize) -> usize {
+    let mut nested: usize = 0;
+
+    for (i, token) in tokens.iter().enumerate().skip(start + 1) {
+      match &token.text[..] {
+        "(" => nested += 1,
+        ")" if nested == 0 => return i + 1,
+        ")" => nested -= 1,
+        _ => (),
+      }
+    }
+
+    tokens.len()
+  }
+
+  // check the operations of the quotation starting at the given position
+  // (stack depth is unknown), returning the position following the quotation
+  fn check_quotation<'a>(&self, ops: &[&'a Token], start: usize, fns: &HashMap<&'a str, (Vec<&'a str>, Vec<&'a Token>)>,
+                         names: &[&'a str], calls: &mut Vec<&'a str>, errors: &mut Vec<CompError>) -> usize {
+    let mut nested: usize = 0;
+
+    for (i, token) in ops.iter().enumerate().skip(start + 1) {
+      match &token.text[..] {
+        "[" => nested += 1,
+        "]" if nested == 0 => {
+          self.check_ops(&ops[start + 1..i], None, fns, names, calls, errors);
+          return i + 1;
+        },
+        "]" => nested -= 1,
+        _ => (),
+      }
+    }
+
+    let error: CompError = CompError::IncompleteQuotation.at(&ops[start].span);
+    if !errors.contains(&error) {
+      errors.push(error);
+    }
+
+    ops.len()
+  }
+
+  // add native command to interpreter
+  fn compose_native(&mut self, name: &str, func: Command, effect: &'static str, description: &'static str, example: &'static str) {
+    let info: CommandInfo = CommandInfo { effect, description, example };
+    self.cmap.insert(name.to_string(), Native { func, info });
+  }
+
+  fn init(&mut self) {
+    // stack manipulation
+    self.compose_native("drop",   Interpreter::c_drop,       "a --",                  "drop top element",                            "1 2 drop => 1");
+    self.compose_native("dup",    Interpreter::c_dup,        "a -- a a",              "duplicate top element",                       "3 dup => 3 3");
+    self.compose_native("swap",   Interpreter::c_swap,       "a b -- b a",            "swap top two elements",                       "1 2 swap => 2 1");
+    self.compose_native("cls",    Interpreter::c_cls,        "... --",                "clear stack",                                 "1 2 3 cls =>");
+    self.compose_native("clr",    Interpreter::c_cls,        "... --",                "clear stack",                                 "1 2 3 clr =>");
+    self.compose_native("roll",   Interpreter::c_roll,       "... a -- a ...",        "roll stack (last element becomes first)",     "1 2 3 roll => 3 1 2");
+    self.compose_native("rot",    Interpreter::c_rot,        "a ... -- ... a",        "rotate stack (first element becomes last)",   "1 2 3 rot => 2 3 1");
+    self.compose_native("rolln",  Interpreter::c_rolln,      "... n -- ...",          "roll top n elements (last becomes nth)",      "1 2 3 4 3 rolln => 1 4 2 3");
+    self.compose_native("rotn",   Interpreter::c_rotn,       "... n -- ...",          "rotate top n elements (nth becomes last)",    "1 2 3 4 3 rotn => 1 3 4 2");
+    self.compose_native("dropn",  Interpreter::c_dropn,      "... n -- ...",          "drop top n elements",                         "1 2 3 4 2 dropn => 1 2");
+    self.compose_native("dupn",   Interpreter::c_dupn,       "... n -- ...",          "duplicate top n elements (as a block)",       "1 2 3 2 dupn => 1 2 3 2 3");
+    self.compose_native("take",   Interpreter::c_take,       "... n -- ...",          "keep only top n elements",                    "1 2 3 4 2 take => 3 4");
+    self.compose_native("skip",   Interpreter::c_dropn,      "... n -- ...",          "discard top n elements",                      "1 2 3 4 2 skip => 1 2");
+    self.compose_native("over",   Interpreter::c_over,       "a b -- a b a",          "copy second element to top of stack",         "1 2 over => 1 2 1");
+    self.compose_native("pick",   Interpreter::c_pick,       "... n -- ... a",        "copy nth element (0 is top) to top of stack", "1 2 3 2 pick => 1 2 3 1");
+    self.compose_native("swapn",  Interpreter::c_swapn,      "... n -- ...",          "swap top element with nth (0 is top)",        "1 2 3 2 swapn => 3 2 1");
+    self.compose_native("nip",    Interpreter::c_nip,        "a b -- b",              "drop second element",                         "1 2 nip => 2");
+    self.compose_native("tuck",   Interpreter::c_tuck,       "a b -- b a b",          "copy top element below second element",       "1 2 tuck => 2 1 2");
+    self.compose_native("2dup",   Interpreter::c_2dup,       "a b -- a b a b",        "duplicate top pair",                          "1 2 2dup => 1 2 1 2");
+    self.compose_native("2drop",  Interpreter::c_2drop,      "a b --",                "drop top pair",                               "1 2 3 2drop => 1");
+    self.compose_native("2swap",  Interpreter::c_2swap,      "a b c d -- c d a b",    "swap top two pairs",                          "1 2 3 4 2swap => 3 4 1 2");
+    self.compose_native("2over",  Interpreter::c_2over,      "a b c d -- a b c d a b", "copy second pair to top of stack",           "1 2 3 4 2over => 1 2 3 4 1 2");
+    self.compose_native("depth",  Interpreter::c_depth,      "-- n",                  "number of elements on stack",                 "1 2 3 depth => 1 2 3 3");
+    self.compose_native("count",  Interpreter::c_depth,      "-- n",                  "number of elements on stack",                 "1 2 3 count => 1 2 3 3");
+    self.compose_native("sort",   Interpreter::c_sort,       "... -- ...",            "sort stack in ascending order",               "3 1 2 sort => 1 2 3");
+    self.compose_native("sortd",  Interpreter::c_sortd,      "... -- ...",            "sort stack in descending order",              "3 1 2 sortd => 3 2 1");
+    self.compose_native("rev",    Interpreter::c_rev,        "... -- ...",            "reverse order of stack elements",             "1 2 3 rev => 3 2 1");
+    self.compose_native("uniq",   Interpreter::c_uniq,       "... -- ...",            "remove duplicate elements (keep first)",      "1 2 1 3 2 uniq => 1 2 3");
+    self.compose_native("uniqt",  Interpreter::c_uniqt,      "... t -- ...",          "remove elements within tolerance t",          "1 1.05 2 0.1 uniqt => 1 2");
+    // memory usage
+    self.compose_native("sa",     Interpreter::c_store_a,    "a --",                  "store value in memory a",                     "5 sa a => 5");
+    self.compose_native(".a",     Interpreter::c_store_a,    "a --",                  "store value in memory a",                     "5 .a a => 5");
+    self.compose_native("a",      Interpreter::c_push_a,     "-- a",                  "retrieve value from memory a",                "5 sa 1 a => 1 5");
+    self.compose_native("sb",     Interpreter::c_store_b,    "a --",                  "store value in memory b",                     "5 sb b => 5");
+    self.compose_native(".b",     Interpreter::c_store_b,    "a --",                  "store value in memory b",                     "5 .b b => 5");
+    self.compose_native("b",      Interpreter::c_push_b,     "-- b",                  "retrieve value from memory b",                "5 sb 1 b => 1 5");
+    self.compose_native("sc",     Interpreter::c_store_c,    "a --",                  "store value in memory c",                     "5 sc c => 5");
+    self.compose_native(".c",     Interpreter::c_store_c,    "a --",                  "store value in memory c",                     "5 .c c => 5");
+    self.compose_native("c",      Interpreter::c_push_c,     "-- c",                  "retrieve value from memory c",                "5 sc 1 c => 1 5");
+    self.compose_native("store",  Interpreter::c_store,      "a --",                  "store value in variable (store <name>)",      "5 store rate recall rate => 5");
+    self.compose_native("local",  Interpreter::c_local,      "a --",                  "store in local variable (local <name>)",      "fn f local n recall n end 5 f => 5");
+    self.compose_native("recall", Interpreter::c_recall,     "-- a",                  "retrieve value of variable (recall <name>)",  "5 store rate 1 recall rate => 1 5");
+    self.compose_native("save",   Interpreter::c_save,       "--",                    "save session to file (save <file>)",          "1 2 save session.json");
+    self.compose_native("load",   Interpreter::c_load,       "... -- ...",            "load session from file (load <file>)",        "load session.json");
+    self.compose_native("wstack", Interpreter::c_wstack,     "--",                    "write stack to file (wstack <file>)",         "1 2 wstack stack.txt");
+    self.compose_native("rstack", Interpreter::c_rstack,     "-- ...",                "push values read from file (rstack <file>)",  "rstack stack.txt");
+    self.compose_native("include", Interpreter::c_include,   "--",                    "evaluate file operations (include <file>)",   "include lib.comp");
+    // math operations
+    self.compose_native("+",      Interpreter::c_add,        "a b -- a+b",            "add",                                         "3 4 + => 7");
+    self.compose_native("+_",     Interpreter::c_add_all,    "... -- sum",            "add all",                                     "1 2 3 4 +_ => 10");
+    self.compose_native("-",      Interpreter::c_sub,        "a b -- a-b",            "subtract",                                    "3 4 - => -1");
+    self.compose_native("x",      Interpreter::c_mult,       "a b -- a*b",            "multiply",                                    "3 4 x => 12");
+    self.compose_native("x_",     Interpreter::c_mult_all,   "... -- product",        "multiply all",                                "1 2 3 4 x_ => 24");
+    self.compose_native("sum",    Interpreter::c_sum,        "... -- sum",            "sum of all elements",                         "1 2 3 4 sum => 10");
+    self.compose_native("mean",   Interpreter::c_mean,       "... -- mean",           "arithmetic mean of all elements",             "1 2 3 4 mean => 2.5");
+    self.compose_native("median", Interpreter::c_median,     "... -- median",         "median of all elements",                      "3 1 4 1 5 median => 3");
+    self.compose_native("var",    Interpreter::c_var,        "... -- var",            "sample variance of all elements",             "2 4 4 4 5 5 7 9 var => 4.571428571428571");
+    self.compose_native("varp",   Interpreter::c_varp,       "... -- var",            "population variance of all elements",         "2 4 4 4 5 5 7 9 varp => 4");
+    self.compose_native("sd",     Interpreter::c_sd,         "... -- sd",             "sample standard deviation",                   "1 3 sd => 1.4142135623730951");
+    self.compose_native("sdp",    Interpreter::c_sdp,        "... -- sd",             "population standard deviation",               "2 4 4 4 5 5 7 9 sdp => 2");
+    self.compose_native("/",      Interpreter::c_div,        "a b -- a/b",            "divide",                                      "3 4 / => 0.75");
+    self.compose_native("chs",    Interpreter::c_chs,        "a -- -a",               "change sign",                                 "3 chs => -3");
+    self.compose_native("abs",    Interpreter::c_abs,        "a -- |a|",              "absolute value",                              "-3 abs => 3");
+    self.compose_native("sign",   Interpreter::c_sign,       "a -- sign(a)",          "sign (-1, 0, or 1)",                          "-3.5 sign => -1");
+    self.compose_native("round",  Interpreter::c_round,      "a -- round(a)",         "round",                                       "10.2 round => 10");
+    self.compose_native("int",    Interpreter::c_round,      "a -- round(a)",         "round",                                       "10.7 int => 11");
+    self.compose_native("roundn", Interpreter::c_roundn,     "a n -- round(a,n)",     "round to n decimal places",                   "2.675 2 roundn => 2.68");
+    self.compose_native("inv",    Interpreter::c_inv,        "a -- 1/a",              "invert (1/x)",                                "4 inv => 0.25");
+    self.compose_native("sqrt",   Interpreter::c_sqrt,       "a -- sqrt(a)",          "square root (complex if a is negative)",      "16 sqrt => 4");
+    self.compose_native("hypot",  Interpreter::c_hypot,      "a b -- sqrt(a^2+b^2)",  "hypotenuse (without overflow)",               "3 4 hypot => 5");
+    self.compose_native("throot", Interpreter::c_throot,     "a n -- a^(1/n)",        "nth root",                                    "9 2 throot => 3");
+    self.compose_native("cbrt",   Interpreter::c_cbrt,       "a -- cbrt(a)",          "cube root",                                   "-27 cbrt => -3");
+    self.compose_native("nroot",  Interpreter::c_nroot,      "a n -- a^(1/n)",        "nth root (real for negative a and odd n)",    "-32 5 nroot => -2");
+    self.compose_native("proot",  Interpreter::c_proot,      "a b c -- r1 i1 r2 i2",  "find principal roots of ax^2+bx+c=0",         "1 0 -9 proot => 3 0 -3 0");
+    self.compose_native("^",      Interpreter::c_exp,        "a b -- a^b",            "exponentiation",                              "2 4 ^ => 16");
+    self.compose_native("exp",    Interpreter::c_exp,        "a b -- a^b",            "exponentiation (alias of ^, not e^a)",        "2 4 exp => 16");
+    self.compose_native("%",      Interpreter::c_mod,        "a b -- a%b",            "modulus",                                     "5 2 % => 1");
+    self.compose_native("mod",    Interpreter::c_mod,        "a b -- a%b",            "modulus",                                     "5 2 mod => 1");
+    self.compose_native("//",     Interpreter::c_floor_div,  "a b -- floor(a/b)",     "floor division",                              "7 2 // => 3");
+    self.compose_native("divmod", Interpreter::c_divmod,     "a b -- q r",            "floor division and remainder (a = q*b + r)",  "-7 2 divmod => -4 1");
+    self.compose_native("pctchg", Interpreter::c_pctchg,     "a b -- (b-a)/a*100",    "percent change from a to b",                  "80 100 pctchg => 25");
+    self.compose_native("!",      Interpreter::c_fact,       "a -- a!",               "factorial (gamma(a+1) for non-integers)",     "5 ! => 120");
+    self.compose_native("ncr",    Interpreter::c_ncr,        "n r -- C(n,r)",         "combinations (n choose r)",                   "52 5 ncr => 2598960");
+    self.compose_native("npr",    Interpreter::c_npr,        "n r -- P(n,r)",         "permutations (ordered selections)",           "10 3 npr => 720");
+    self.compose_native("gamma",  Interpreter::c_gamma,      "a -- gamma(a)",         "gamma function",                              "5 gamma => 24");
+    self.compose_native("lngamma", Interpreter::c_lngamma,   "a -- ln|gamma(a)|",     "logarithm of the absolute gamma function",    "1000 lngamma => 5905.220423209181");
+    self.compose_native("gcd",    Interpreter::c_gcd,        "a b -- gcd(a,b)",       "greatest common divisor",                     "10 55 gcd => 5");
+    self.compose_native("lcm",    Interpreter::c_lcm,        "a b -- lcm(a,b)",       "least common multiple",                       "4 6 lcm => 12");
+    self.compose_native("modpow", Interpreter::c_modpow,     "b e m -- b^e%m",        "modular exponentiation",                      "7 128 13 modpow => 3");
+    self.compose_native("modinv", Interpreter::c_modinv,     "a m -- a^-1%m",         "modular multiplicative inverse",              "3 11 modinv => 4");
+    self.compose_native("isprime", Interpreter::c_isprime,   "n -- bool",             "1 if n is prime, 0 otherwise",                "97 isprime => 1");
+    self.compose_native("nextprime", Interpreter::c_nextprime, "n -- p",              "smallest prime greater than n",               "100 nextprime => 101");
+    self.compose_native("prevprime", Interpreter::c_prevprime, "n -- p",              "largest prime less than n",                   "100 prevprime => 97");
+    self.compose_native("factor", Interpreter::c_factor,     "n -- p1 ... pk",        "prime factorization (ascending)",             "360 factor => 2 2 2 3 3 5");
+    self.compose_native("fib",    Interpreter::c_fib,        "n -- F(n)",             "nth Fibonacci number",                        "10 fib => 55");
+    self.compose_native("lucas",  Interpreter::c_lucas,      "n -- L(n)",             "nth Lucas number",                            "10 lucas => 123");
+    self.compose_native("min",    Interpreter::c_min,        "a b -- min(a,b)",       "minimum",                                     "3 4 min => 3");
+    self.compose_native("max",    Interpreter::c_max,        "a b -- max(a,b)",       "maximum",                                     "3 4 max => 4");
+    self.compose_native("min_",   Interpreter::c_min_all,    "... -- min",            "minimum of all",                              "3 1 4 2 min_ => 1");
+    self.compose_native("max_",   Interpreter::c_max_all,    "... -- max",            "maximum of all",                              "3 1 4 2 max_ => 4");
+    self.compose_native("clamp",  Interpreter::c_clamp,      "a lo hi -- a'",         "bound a to the range [lo, hi]",               "12 0 10 clamp => 10");
+    self.compose_native("lerp",   Interpreter::c_lerp,       "a b t -- a+(b-a)t",     "linear interpolation from a to b",            "10 20 0.25 lerp => 12.5");
+    self.compose_native("invlerp", Interpreter::c_invlerp,   "a b v -- (v-a)/(b-a)",  "inverse linear interpolation",                "10 20 12.5 invlerp => 0.25");
+    self.compose_native("remap",  Interpreter::c_remap,      "v a b c d -- v'",       "map v from range [a, b] to range [c, d]",     "50 32 212 0 100 remap => 10");
+    self.compose_native("pi",     Interpreter::c_pi,         "-- pi",                 "pi",                                          "pi => 3.141592653589793");
+    self.compose_native("e",      Interpreter::c_euler,      "-- e",                  "Euler's number",                              "e => 2.718281828459045");
+    self.compose_native("d_r",    Interpreter::c_dtor,       "a -- rad(a)",           "degrees to radians",                          "180 d_r => 3.141592653589793");
+    self.compose_native("r_d",    Interpreter::c_rtod,       "a -- deg(a)",           "radians to degrees",                          "pi r_d => 180");
+    self.compose_native("dms",    Interpreter::c_dms,        "a -- d m s",            "decimal degrees to degrees, minutes, seconds", "30.5125 dms => 30 30 45");
+    self.compose_native("ddeg",   Interpreter::c_ddeg,       "d m s -- a",            "degrees, minutes, seconds to decimal degrees", "30 30 45 ddeg => 30.5125");
+    self.compose_native("sin",    Interpreter::c_sin,        "a -- sin(a)",           "sine",                                        "pi 2 / sin => 1");
+    self.compose_native("asin",   Interpreter::c_asin,       "a -- asin(a)",          "arcsine",                                     "1 asin => 1.5707963267948966");
+    self.compose_native("cos",    Interpreter::c_cos,        "a -- cos(a)",           "cosine",                                      "0 cos => 1");
+    self.compose_native("acos",   Interpreter::c_acos,       "a -- acos(a)",          "arccosine",                                   "1 acos => 0");
+    self.compose_native("tan",    Interpreter::c_tan,        "a -- tan(a)",           "tangent",                                     "pi 4 / tan => 0.9999999999999999");
+    self.compose_native("atan",   Interpreter::c_atan,       "a -- atan(a)",          "arctangent",                                  "1 atan => 0.7853981633974483");
+    self.compose_native("atan2",  Interpreter::c_atan2,      "y x -- atan2(y,x)",     "arctangent of y/x (in the quadrant of (x,y))", "-1 -1 atan2 => -2.356194490192345");
+    self.compose_native("sinh",   Interpreter::c_sinh,       "a -- sinh(a)",          "hyperbolic sine",                             "1 sinh => 1.1752011936438014");
+    self.compose_native("cosh",   Interpreter::c_cosh,       "a -- cosh(a)",          "hyperbolic cosine",                           "0 cosh => 1");
+    self.compose_native("tanh",   Interpreter::c_tanh,       "a -- tanh(a)",          "hyperbolic tangent",                          "1 tanh => 0.7615941559557649");
+    self.compose_native("asinh",  Interpreter::c_asinh,      "a -- asinh(a)",         "inverse hyperbolic sine",                     "1 asinh => 0.881373587019543");
+    self.compose_native("acosh",  Interpreter::c_acosh,      "a -- acosh(a)",         "inverse hyperbolic cosine",                   "1 acosh => 0");
+    self.compose_native("atanh",  Interpreter::c_atanh,      "a -- atanh(a)",         "inverse hyperbolic tangent",                  "0.5 atanh => 0.5493061443340548");
+    self.compose_native("log2",   Interpreter::c_log2,       "a -- log2(a)",          "logarithm (base 2)",                          "256 log2 => 8");
+    self.compose_native("log",    Interpreter::c_log10,      "a -- log10(a)",         "logarithm (base 10)",                         "100 log => 2");
+    self.compose_native("log10",  Interpreter::c_log10,      "a -- log10(a)",         "logarithm (base 10)",                         "100 log10 => 2");
+    self.compose_native("logn",   Interpreter::c_logn,       "a n -- logn(a)",        "logarithm (base n)",                          "256 2 logn => 8");
+    self.compose_native("ln",     Interpreter::c_ln,         "a -- ln(a)",            "natural logarithm",                           "e ln => 1");
+    self.compose_native("e^",     Interpreter::c_exp_e,      "a -- e^a",              "natural exponential",                         "1 e^ => 2.718281828459045");
+    self.compose_native("exp2",   Interpreter::c_exp2,       "a -- 2^a",              "base 2 exponential",                          "0.5 exp2 => 1.4142135623730951");
+    self.compose_native("expm1",  Interpreter::c_expm1,      "a -- e^a-1",            "natural exponential minus 1 (accurate near 0)", "1e-10 expm1 => 1.00000000005e-10");
+    self.compose_native("ln1p",   Interpreter::c_ln1p,       "a -- ln(1+a)",          "natural logarithm of 1+a (accurate near 0)",  "1e-10 ln1p => 9.999999999500001e-11");
+    self.compose_native("re",     Interpreter::c_re,         "a -- re(a)",            "real part",                                   "3+4i re => 3");
+    self.compose_native("im",     Interpreter::c_im,         "a -- im(a)",            "imaginary part",                              "3+4i im => 4");
+    self.compose_native("conj",   Interpreter::c_conj,       "a -- conj(a)",          "complex conjugate",                           "3+4i conj => 3-4i");
+    self.compose_native("arg",    Interpreter::c_arg,        "a -- arg(a)",           "argument (phase angle)",                      "1i arg => 1.5707963267948966");
+    self.compose_native("mag",    Interpreter::c_mag,        "a -- |a|",              "magnitude",                                   "3+4i mag => 5");
+    self.compose_native("tofloat", Interpreter::c_tofloat,   "a -- float(a)",         "convert to floating point",                   "1/4 tofloat => 0.25");
+    self.compose_native("tofrac", Interpreter::c_tofrac,     "a tol -- n d",          "simplest fraction n/d within tol of a",       "pi 0.01 tofrac => 22 7");
+    self.compose_native("roman",  Interpreter::c_roman,      "n -- roman(n)",         "Roman numeral (1 to 3999)",                   "1994 roman => MCMXCIV");
+    self.compose_native("unroman", Interpreter::c_unroman,   "roman -- n",            "value of Roman numeral",                      "\"xiv\" unroman => 14");
+    self.compose_native("chr",    Interpreter::c_chr,        "n -- chr(n)",           "character of Unicode code point",             "65 chr => A");
+    self.compose_native("ord",    Interpreter::c_ord,        "chr -- n",              "Unicode code point of character",             "\"é\" ord hex => 0xe9");
+    self.compose_native("bits",   Interpreter::c_bits,       "a -- bits(a)",          "IEEE-754 bit pattern of floating point value", "1 bits hex => 0x3ff0000000000000");
+    self.compose_native("frombits", Interpreter::c_frombits, "n -- float(n)",         "floating point value of IEEE-754 bit pattern", "0x3ff8000000000000 frombits => 1.5");
+    self.compose_native("mantissa", Interpreter::c_mantissa, "a -- m",                "significand (a = m*2^e with 1 <= |m| < 2)",   "12 mantissa => 1.5");
+    self.compose_native("expnt",  Interpreter::c_expnt,      "a -- e",                "binary exponent (a = m*2^e with 1 <= |m| < 2)", "12 expnt => 3");
+    self.compose_native("ulp",    Interpreter::c_ulp,        "a -- ulp(a)",           "unit in the last place",                      "1 ulp => 2.220446049250313e-16");
+    self.compose_native("nextafter", Interpreter::c_nextafter, "a b -- a'",           "next floating point value after a toward b",  "1 2 nextafter => 1.0000000000000002");
+    self.compose_native("isnan",  Interpreter::c_isnan,      "a -- bool",             "1 if a is NaN, 0 otherwise",                  "nan isnan => 1");
+    self.compose_native("isinf",  Interpreter::c_isinf,      "a -- bool",             "1 if a is infinite, 0 otherwise",             "1 0 / isinf => 1");
+    self.compose_native("isfinite", Interpreter::c_isfinite, "a -- bool",             "1 if a is neither NaN nor infinite",          "2 sqrt isfinite => 1");
+    // bitwise operations
+    self.compose_native("and",    Interpreter::c_and,        "a b -- a&b",            "bitwise and",                                 "0b1100 0b1010 and bin => 0b1000");
+    self.compose_native("or",     Interpreter::c_or,         "a b -- a|b",            "bitwise or",                                  "0b1100 0b1010 or bin => 0b1110");
+    self.compose_native("xor",    Interpreter::c_xor,        "a b -- a^b",            "bitwise exclusive or",                        "0b1100 0b1010 xor bin => 0b110");
+    self.compose_native("not",    Interpreter::c_not,        "a -- ~a",               "bitwise not (two's complement, -a-1)",        "0 not => -1");
+    self.compose_native("shl",    Interpreter::c_shl,        "a n -- a<<n",           "shift left by n bits",                        "1 8 shl => 256");
+    self.compose_native("shr",    Interpreter::c_shr,        "a n -- a>>n",           "shift right by n bits (arithmetic)",          "256 4 shr => 16");
+    // comparison operations
+    self.compose_native("<",      Interpreter::c_lt,         "a b -- bool",           "1 if a is less than b, 0 otherwise",          "3 4 < => 1");
+    self.compose_native(">",      Interpreter::c_gt,         "a b -- bool",           "1 if a is greater than b, 0 otherwise",       "3 4 > => 0");
+    self.compose_native("<=",     Interpreter::c_le,         "a b -- bool",           "1 if a is less than or equal to b",           "4 4 <= => 1");
+    self.compose_native(">=",     Interpreter::c_ge,         "a b -- bool",           "1 if a is greater than or equal to b",        "3 4 >= => 0");
+    self.compose_native("==",     Interpreter::c_eq,         "a b -- bool",           "1 if a is equal to b, 0 otherwise",           "1/2 0.5 == => 1");
+    self.compose_native("!=",     Interpreter::c_ne,         "a b -- bool",           "1 if a is not equal to b, 0 otherwise",       "1/3 0.3 != => 1");
+    // logical operations
+    self.compose_native("land",   Interpreter::c_land,       "a b -- bool",           "logical and (1 if both are true)",            "1 2 < 5 0 > land => 1");
+    self.compose_native("lor",    Interpreter::c_lor,        "a b -- bool",           "logical or (1 if either is true)",            "0 3 lor => 1");
+    self.compose_native("lnot",   Interpreter::c_lnot,       "a -- bool",             "logical not (1 if false)",                    "0 lnot => 1");
+    self.compose_native("lxor",   Interpreter::c_lxor,       "a b -- bool",           "logical exclusive or (1 if one is true)",     "1 5 lxor => 0");
+    // random numbers
+    self.compose_native("rand",   Interpreter::c_rand,       "-- r",                  "uniform random number in [0, 1)",             "rand");
+    self.compose_native("randint", Interpreter::c_randint,   "a b -- n",              "uniform random integer in [a, b]",            "1 6 randint");
+    self.compose_native("randn",  Interpreter::c_randn,      "-- r",                  "standard normal random number",               "randn");
+    self.compose_native("randexp", Interpreter::c_randexp,   "rate -- r",             "exponential random number (mean 1/rate)",     "2 randexp");
+    self.compose_native("randpois", Interpreter::c_randpois, "mean -- n",             "Poisson random integer",                      "4 randpois");
+    self.compose_native("seed",   Interpreter::c_seed,       "n --",                  "seed random number generator",               "42 seed rand");
+    self.compose_native("shuffle", Interpreter::c_shuffle,   "... -- ...",            "shuffle stack in random order",               "1 2 3 4 shuffle");
+    // sequences
+    self.compose_native("range",  Interpreter::c_range,      "a b s -- ...",          "integers from a to b (inclusive) in steps s", "1 9 2 range => 1 3 5 7 9");
+    self.compose_native("linspace", Interpreter::c_linspace, "a b n -- ...",          "n evenly spaced numbers from a to b",         "0 1 5 linspace => 0 0.25 0.5 0.75 1");
+    // display
+    self.compose_native("hex",    Interpreter::c_hex,        "--",                    "display integers in hexadecimal",             "255 hex => 0xff");
+    self.compose_native("bin",    Interpreter::c_bin,        "--",                    "display integers in binary",                  "10 bin => 0b1010");
+    self.compose_native("oct",    Interpreter::c_oct,        "--",                    "display integers in octal",                   "493 oct => 0o755");
+    self.compose_native("dec",    Interpreter::c_dec,        "--",                    "display integers in decimal",                 "0xff hex dec => 255");
+    self.compose_native("prec",   Interpreter::c_prec,       "n --",                  "display n decimal places (-1 for full)",      "2 sqrt 4 prec => 1.4142");
+    self.compose_native("sci",    Interpreter::c_sci,        "--",                    "display numbers in scientific notation",      "1230000 sci => 1.23e6");
+    self.compose_native("fix",    Interpreter::c_fix,        "--",                    "display numbers in fixed notation",           "1e20 fix => 100000000000000000000");
+    self.compose_native("eng",    Interpreter::c_eng,        "--",                    "display numbers in engineering notation",     "4700 eng => 4.7e3");
+    self.compose_native("si",     Interpreter::c_si,         "--",                    "display numbers with SI prefixes",            "0.000000022 si => 22n");
+    self.compose_native("group",  Interpreter::c_group,      "--",                    "display digits in groups of thousands",       "1234567 group => 1,234,567");
+    self.compose_native("ungroup", Interpreter::c_ungroup,   "--",                    "display digits without grouping",             "1234567 group ungroup => 1234567");
+    self.compose_native("auto",   Interpreter::c_auto,       "--",                    "display numbers in automatic notation",       "1e20 fix auto => 1e20");
+    self.compose_native("print",  Interpreter::c_print,      "a -- a",                "print top element immediately",               "3 print => 3");
+    self.compose_native("pstack", Interpreter::c_pstack,     "--",                    "print stack immediately",                     "1 2 pstack => 1 2");
+    // control flow
+    self.compose_native("fn",     Interpreter::c_fn,         "--",                    "function definition (fn <name> ... end)",     "fn square dup x end 3 square => 9");
+    self.compose_native("alias",  Interpreter::c_alias,      "--",                    "name for command (alias <new> <existing>)",   "alias neg chs 3 neg => -3");
+    self.compose_native("(",      Interpreter::c_comment,    "--",                    "comment (( ... ))",                           "1 ( comment ) 2 => 1 2");
+    self.compose_native("[",      Interpreter::c_quote,      "-- q",                  "push quotation of operations up to ]",        "[ dup x ] => [ dup x ]");
+    self.compose_native("call",   Interpreter::c_call,       "... q -- ...",          "call quotation",                              "3 [ dup x ] call => 9");
+    self.compose_native("if",     Interpreter::c_if,         "c --",                  "call function if c is true (if <fn>)",        "5 1 if chs => -5");
+    self.compose_native("ifelse", Interpreter::c_ifelse,     "c --",                  "call one of two functions (ifelse <t> <f>)",  "5 0 ifelse chs sqrt => 2.23606797749979");
+    self.compose_native("while",  Interpreter::c_while,      "... -- ...",            "loop while condition (while <cond> <body>)",  "fn small dup 100 < end fn sq dup x end 2 while small sq => 256");
+    self.compose_native("times",  Interpreter::c_times,      "n -- ...",              "call function n times (times <fn>)",          "fn sq dup x end 2 3 times sq => 256");
+    self.compose_native("for",    Interpreter::c_for,        "a b -- ...",            "call function for i = a to b (for <fn>)",     "0 1 4 for + => 10");
+    self.compose_native("again",  Interpreter::c_again,      "... -- ...",            "repeat last command",                         "3 2 x again again => 24");
+    self.compose_native("redo",   Interpreter::c_redo,       "... n -- ...",          "repeat last command n times",                 "3 2 x 3 redo => 48");
+    self.compose_native("undo",   Interpreter::c_undo,       "... -- ...",            "undo previous operation",                     "1 2 + undo => 1 2");
+    self.compose_native("map",    Interpreter::c_map,        "... -- ...",            "apply function to each element (map <fn>)",   "1 4 9 map sqrt => 1 2 3");
+    self.compose_native("filter", Interpreter::c_filter,     "... -- ...",            "keep elements passing test (filter <fn>)",    "4 5 6 7 filter isprime => 5 7");
+    self.compose_native("fold",   Interpreter::c_fold,       "... init -- acc",       "fold elements into init (fold <fn>)",         "1 2 3 10 fold - => 4");
+    self.compose_native("reduce", Interpreter::c_reduce,     "... -- acc",            "fold elements into first (reduce <fn>)",      "10 1 2 reduce - => 7");
+    self.compose_native("zipwith", Interpreter::c_zipwith,   "a.. b.. -- ...",        "combine stack halves (zipwith <fn>)",         "1 2 3 4 zipwith - => -2 -2");
+    self.compose_native("zip+",   Interpreter::c_zip_add,    "a.. b.. -- ...",        "add stack halves element-wise",               "1 2 3 4 zip+ => 4 6");
+    self.compose_native("zipx",   Interpreter::c_zip_mult,   "a.. b.. -- ...",        "multiply stack halves element-wise",          "1 2 3 4 zipx => 3 8");
+    self.compose_native("keep",   Interpreter::c_keep,       "... -- ...",            "keep operands of function (keep <fn>)",       "3 4 keep + => 3 4 7");
+  }
+
+  fn process_node(&mut self, op: &str) -> Result<(), CompError> {
+    // parameters and local variables of the current user function call
+    // precede commands
+    if let Some(value) = self.frames.last().and_then(|frame| frame.vars.get(op)) {
+      self.stack.push(value.clone());
+      return Ok(());
+    }
+
+    if self.cmap.contains_key(op) { // native comp command?
+      let f = self.cmap[op].func;
+      f(self, op)?;
+
+      // strict mode rejects NaN and infinite results
+      if self.config.strict && self.stack.last().is_some_and(|a| !a.is_finite()) {
+        self.stack.pop();
+        return Err(CompError::NonFinite(op.to_string()));
+      }
+    } else {
+      let result: Option<usize> = self.is_user_function(op); // user-defined function?
+
+      match result {
+        Some(index) => { // user-defined function
+          if self.nesting == 0 && self.outer_ops.is_none() {
+            self.outer_ops = Some(self.ops.len());
+          }
+
+          // declared parameters are bound to the top elements of the stack
+          // (the last parameter to the top element)
+          let params: Vec<String> = self.fns[index].params.clone();
+          Interpreter::check_stack_error(self, params.len(), op)?;
+          let mut vars: HashMap<String, Value> = HashMap::new();
+          for param in params.into_iter().rev() {
+            let value: Value = self.pop_stack()?;
+            vars.insert(param, value);
+          }
+
+          // a call that ends the calling function replaces its local
+          // variables (so that tail calls do not accumulate)
+          self.close_frames();
+          if self.frames.len() >= self.config.max_depth {
+            return Err(CompError::NestingLimit(self.config.max_depth));
+          }
+          self.frames.push(Frame { nesting: self.nesting, end: self.ops.len(), vars });
+
+          // copy user function ops (fops) into main ops
+          for i in (0..self.fns[index].fops.len()).rev() {
+            let fop: Token = self.fns[index].fops[i].clone();
+            self.ops.push_front(fop);
+          }
+        }
+        None => { // neither native command nor user-defined function
+          // parse value and push onto stack
+          match self.parse_value(op) {
+            Some(value) => self.stack.push(value),
+            None => return Err(CompError::UnknownExpression(op.to_string())),
+          }
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  // pop from stack helpers ----------------------------------------------------
+  fn pop_stack(&mut self) -> Result<Value, CompError> {
+    self.stack.pop().ok_or(CompError::EmptyStack)
+  }
+
+  fn pop_stack_f(&mut self) -> Result<f64, CompError> {
+    let element: Value = self.pop_stack_v()?;
+    Ok(element.to_f64())
+  }
+
+  fn pop_stack_i(&mut self) -> Result<BigInt, CompError> {
+    let element: Value = self.pop_stack()?;
+    element.to_integer()
+      .ok_or_else(|| CompError::NotAnInteger(element.to_string()))
+  }
+
+  // pop number (text values are not numbers)
+  fn pop_stack_v(&mut self) -> Result<Value, CompError> {
+    match self.pop_stack()? {
+      Value::Str(text) => Err(CompError::NotANumber(text)),
+      element @ Value::Block(_) => Err(CompError::NotANumber(element.to_string())),
+      element => Ok(element),
+    }
+  }
+
+  fn pop_stack_n(&mut self) -> Result<BigInt, CompError> {
+    let element: Value = self.pop_stack()?;
+    element.to_integer()
+      .filter(|i| !i.is_negative())
+      .ok_or_else(|| CompError::NotAnInteger(element.to_string()))
+  }
+  // ---------------------------------------------------------------------------
+
+  // pop two operands and push the result of the exact operation when both
+  // operands are exact (integers or fractions). the floating point operation
+  // is used otherwise, when the exact operation returns `None`, or when the
+  // exact result is a fraction of integer operands outside of rational mode.
+  // in integer mode, operands must be integers and the result is truncated.
+  // the complex operation (if any) is used when either operand is complex
+  fn binary_op(&mut self, exact_op: fn(&BigRational, &BigRational) -> Option<BigRational>, float_op: fn(f64, f64) -> f64,
+               complex_op: Option<fn(Complex64, Complex64) -> Complex64>) -> Result<(), CompError> {
+    let b: Value = self.pop_stack_v()?;
+    let a: Value = self.pop_stack_v()?;
+
+    if self.config.integer {
+      let x: BigRational = Interpreter::integer_operand(&a)?;
+      let y: BigRational = Interpreter::integer_operand(&b)?;
+      self.stack.push(Interpreter::integer_result(exact_op(&x, &y))?);
+      return Ok(());
+    }
+
+    if let (Value::Complex(_), _) | (_, Value::Complex(_)) = (&a, &b) {
+      if let Some(complex_op) = complex_op {
+        self.stack.push(Value::from(complex_op(a.to_complex(), b.to_complex())));
+        return Ok(());
+      }
+    }
+
+    let exact: Option<BigRational> = match (a.to_rational(), b.to_rational()) {
+      (Some(x), Some(y)) => exact_op(&x, &y),
+      _ => None,
+    };
+    let fraction: bool = self.config.rational || matches!(a, Value::Ratio(_)) || matches!(b, Value::Ratio(_));
+    self.stack.push(match exact {
+      Some(r) if fraction || r.is_integer() => Value::from(r),
+      _ => Value::Float(float_op(a.to_f64(), b.to_f64())),
+    });
+
+    Ok(())
+  }
+
+  // integer mode operand (as a rational number)
+  fn integer_operand(a: &Value) -> Result<BigRational, CompError> {
+    match a.to_bigint() {
+      Some(i) => Ok(BigRational::from_integer(i)),
+      None => Err(CompError::NotAnInteger(a.to_string())),
+    }
+  }
+
+  // integer mode result (truncated toward zero and checked against the
+  // 128-bit integer range)
+  fn integer_result(r: Option<BigRational>) -> Result<Value, CompError> {
+    let i: BigInt = r.ok_or(CompError::IntegerOverflow)?.to_integer();
+
+    match i.to_i128() {
+      Some(_) => Ok(Value::from(i)),
+      None => Err(CompError::IntegerOverflow),
+    }
+  }
+
+  // pop an operand and push the result of the exact operation when the
+  // operand is exact (see binary_op)
+  fn unary_op(&mut self, exact_op: fn(&BigRational) -> Option<BigRational>, float_op: fn(f64) -> f64,
+              complex_op: Option<fn(Complex64) -> Complex64>) -> Result<(), CompError> {
+    let a: Value = self.pop_stack_v()?;
+
+    if self.config.integer {
+      let x: BigRational = Interpreter::integer_operand(&a)?;
+      self.stack.push(Interpreter::integer_result(exact_op(&x))?);
+      return Ok(());
+    }
+
+    if let (Value::Complex(z), Some(complex_op)) = (&a, complex_op) {
+      self.stack.push(Value::from(complex_op(*z)));
+      return Ok(());
+    }
+
+    let exact: Option<BigRational> = a.to_rational().and_then(|x| exact_op(&x));
+    let fraction: bool = self.config.rational || matches!(a, Value::Ratio(_));
+    self.stack.push(match exact {
+      Some(r) if fraction || r.is_integer() => Value::from(r),
+      _ => Value::Float(float_op(a.to_f64())),
+    });
+
+    Ok(())
+  }
+
+  // round value to the given number of decimal places using the configured
+  // rounding mode. floating point values are rounded as their shortest
+  // decimal representation (e.g., 2.675 rather than 2.67499999...)
+  fn round_value(&self, a: &Value, places: i32) -> Value {
+    let scale: BigRational = BigRational::from_integer(BigInt::from(10).pow(places.unsigned_abs()));
+    let scale: BigRational = if places < 0 { scale.recip() } else { scale };
+    let round = |r: BigRational| -> BigRational {
+      BigRational::from_integer(Interpreter::round_rational(&(r * &scale), self.config.rounding)) / &scale
+    };
+    let round_float = |x: f64| -> f64 {
+      match Interpreter::decimal_rational(x) {
+        Some(r) => round(r).to_f64().unwrap_or(f64::NAN),
+        None => x, // NaN or infinite
+      }
+    };
+
+    match a {
+      Value::Float(x) => Value::Float(round_float(*x)),
+      Value::Complex(z) => Value::from(Complex64::new(round_float(z.re), round_float(z.im))),
+      _ => match a.to_rational() {
+        Some(r) => Value::from(round(r)),
+        None => a.clone(),
+      },
+    }
+  }
+
+  // round rational number to an integer using the given rounding mode
+  fn round_rational(r: &BigRational, rounding: Rounding) -> BigInt {
+    match rounding {
+      Rounding::HalfUp => r.round().to_integer(),
+      Rounding::TowardZero => r.trunc().to_integer(),
+      Rounding::HalfEven => {
+        let floor: BigInt = r.floor().to_integer();
+        let fraction: BigRational = r - BigRational::from_integer(floor.clone());
+        match fraction.cmp(&BigRational::new(1.into(), 2.into())) {
+          Ordering::Less => floor,
+          Ordering::Greater => floor + 1,
+          Ordering::Equal if floor.is_even() => floor,
+          Ordering::Equal => floor + 1,
+        }
+      },
+    }
+  }
+
+  // exact value of the shortest decimal representation of a finite floating
+  // point number
+  fn decimal_rational(x: f64) -> Option<BigRational> {
+    if !x.is_finite() {
+      return None;
+    }
+
+    let formatted: String = format!("{x:e}");
+    let (mantissa, exponent) = formatted.split_once('e')?;
+    let exponent: i32 = exponent.parse().ok()?;
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits: BigInt = format!("{integer}{fraction}").parse().ok()?;
+    let exponent: i32 = exponent - fraction.len() as i32;
+    let power: BigRational = BigRational::from_integer(BigInt::from(10).pow(exponent.unsigned_abs()));
+
+    Some(if exponent < 0 { BigRational::from_integer(digits) / power } else { BigRational::from_integer(digits) * power })
+  }
+
+  // convert angle in current angle mode to radians
+  fn angle_in(&self, a: f64) -> f64 {
+    match self.config.angle {
+      AngleMode::Radians => a,
+      AngleMode::Degrees => a.to_radians(),
+    }
+  }
+
+  // convert angle in radians to current angle mode
+  fn angle_out(&self, a: f64) -> f64 {
+    match self.config.angle {
+      AngleMode::Radians => a,
+      AngleMode::Degrees => a.to_degrees(),
+    }
+  }
+
+  // pop argument (following operation) from operations list
+  fn pop_argument(&mut self, op: &str) -> Result<String, CompError> {
+    match self.ops.pop_front() {
+      Some(token) => Ok(token.text),
+      None => Err(CompError::MissingArgument(op.to_string())),
+    }
+  }
+
+  // confirm stack depth
+  fn check_stack_error(&self, min_depth: usize, command: &str) -> Result<(), CompError> {
+    if self.stack.len() < min_depth {
+      return Err(CompError::StackUnderflow { op: command.to_string(), depth: min_depth });
+    }
+
+    Ok(())
+  }
+
+
+  // command functions ---------------------------------------------------------
+  // ---- stack manipulation ---------------------------------------------------
+
+  fn c_drop(&mut self, op: &str) -> Result<(), CompError> {
+    if !self.stack.is_empty() {
+      self.stack.pop();
+    } else {
+      println!("{}: [{}] operation called on empty stack", "warning".bright_yellow(), op.to_string().cyan());
+    }
+
+    Ok(())
+  }
+
+  fn c_dup(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack()?;
+
+    self.stack.push(a.clone());
+    self.stack.push(a);
+
+    Ok(())
+  }
+
+  fn c_swap(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let end: usize = self.stack.len() - 1;
+    self.stack.swap(end, end-1);
+
+    Ok(())
+  }
+
+  fn c_cls(&mut self, _op: &str) -> Result<(), CompError> {
+    self.stack.clear();
+
+    Ok(())
+  }
+
+  fn c_roll(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let o: Value = self.pop_stack()?; // remove last
+    self.stack.splice(0..0, [o]);    // add as first
+
+    Ok(())
+  }
+
+  fn c_rot(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let o: Value = self.stack.remove(0); // remove first
+    self.stack.push(o);                  // add as last
+
+    Ok(())
+  }
+
+  fn c_rolln(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let start: usize = self.pop_block(op)?;
+    if start < self.stack.len() {
+      self.stack[start..].rotate_right(1);
+    }
+
+    Ok(())
+  }
+
+  fn c_rotn(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let start: usize = self.pop_block(op)?;
+    if start < self.stack.len() {
+      self.stack[start..].rotate_left(1);
+    }
+
+    Ok(())
+  }
+
+  fn c_dropn(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let start: usize = self.pop_block(op)?;
+    self.stack.truncate(start);
+
+    Ok(())
+  }
+
+  fn c_take(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let start: usize = self.pop_block(op)?;
+    self.stack.drain(..start);
+
+    Ok(())
+  }
+
+  fn c_dupn(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let start: usize = self.pop_block(op)?;
+    self.stack.extend_from_within(start..);
+
+    Ok(())
+  }
+
+  // pop element count n and return the start of the block of the top n
+  // elements
+  fn pop_block(&mut self, op: &str) -> Result<usize, CompError> {
+    let n: BigInt = self.pop_stack_n()?;
+    let n: usize = n.to_usize().unwrap_or(usize::MAX);
+    if n > self.stack.len() {
+      return Err(CompError::StackUnderflow { op: op.to_string(), depth: n.saturating_add(1) });
+    }
+
+    Ok(self.stack.len() - n)
+  }
+
+  fn c_sort(&mut self, op: &str) -> Result<(), CompError> {
+    self.check_real(op)?;
+
+    self.stack.sort_by(Interpreter::total_order);
+
+    Ok(())
+  }
+
+  fn c_sortd(&mut self, op: &str) -> Result<(), CompError> {
+    self.check_real(op)?;
+
+    self.stack.sort_by(|a, b| Interpreter::total_order(b, a));
+
+    Ok(())
+  }
+
+  fn c_rev(&mut self, _op: &str) -> Result<(), CompError> {
+    self.stack.reverse();
+
+    Ok(())
+  }
+
+  fn c_uniq(&mut self, _op: &str) -> Result<(), CompError> {
+    self.dedup_stack(|a, b| a == b || Interpreter::compare(a, b) == Some(Ordering::Equal));
+
+    Ok(())
+  }
+
+  fn c_uniqt(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let tolerance: f64 = self.pop_stack_f()?;
+    if tolerance.is_nan() || tolerance < 0.0 {
+      return Err(CompError::InvalidArgument { op: op.to_string(), value: tolerance.to_string() });
+    }
+
+    // text values and quotations are only duplicates of identical values
+    self.dedup_stack(|a, b| match (a, b) {
+      (Value::Str(_) | Value::Block(_), _) | (_, Value::Str(_) | Value::Block(_)) => Interpreter::equal(a, b),
+      _ => a == b || (a.to_complex() - b.to_complex()).norm() <= tolerance,
+    });
+
+    Ok(())
+  }
+
+  // remove stack elements that are duplicates of an earlier element (order of
+  // the remaining elements is preserved)
+  fn dedup_stack(&mut self, duplicate: impl Fn(&Value, &Value) -> bool) {
+    let mut unique: Vec<Value> = Vec::with_capacity(self.stack.len());
+    for element in self.stack.drain(..) {
+      if !unique.iter().any(|kept| duplicate(kept, &element)) {
+        unique.push(element);
+      }
+    }
+    self.stack = unique;
+  }
+
+  // confirm that the stack holds only real numbers
+  fn check_real(&self, op: &str) -> Result<(), CompError> {
+    for element in &self.stack {
+      match element {
+        Value::Str(text) => return Err(CompError::NotANumber(text.clone())),
+        Value::Block(_) => return Err(CompError::NotANumber(element.to_string())),
+        Value::Complex(_) => return Err(CompError::InvalidArgument { op: op.to_string(), value: element.to_string() }),
+        _ => (),
+      }
+    }
+
+    Ok(())
+  }
+
+  fn c_over(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let a: Value = self.stack[self.stack.len() - 2].clone();
+    self.stack.push(a);
+
+    Ok(())
+  }
+
+  fn c_pick(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let n: BigInt = self.pop_stack_n()?;
+    let n: usize = n.to_usize().unwrap_or(usize::MAX);
+    if n >= self.stack.len() {
+      return Err(CompError::StackUnderflow { op: op.to_string(), depth: n.saturating_add(2) });
+    }
+
+    let a: Value = self.stack[self.stack.len() - 1 - n].clone();
+    self.stack.push(a);
+
+    Ok(())
+  }
+
+  fn c_swapn(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let n: BigInt = self.pop_stack_n()?;
+    let n: usize = n.to_usize().unwrap_or(usize::MAX);
+    if n >= self.stack.len() {
+      return Err(CompError::StackUnderflow { op: op.to_string(), depth: n.saturating_add(2) });
+    }
+
+    let top: usize = self.stack.len() - 1;
+    self.stack.swap(top, top - n);
+
+    Ok(())
+  }
+
+  fn c_nip(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    self.stack.remove(self.stack.len() - 2);
+
+    Ok(())
+  }
+
+  fn c_tuck(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: Value = self.stack[self.stack.len() - 1].clone();
+    self.stack.insert(self.stack.len() - 2, b);
+
+    Ok(())
+  }
+
+  fn c_2dup(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    self.stack.extend_from_within(self.stack.len() - 2..);
+
+    Ok(())
+  }
+
+  fn c_2drop(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    self.stack.truncate(self.stack.len() - 2);
+
+    Ok(())
+  }
+
+  fn c_2swap(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 4, op)?;
+
+    let start: usize = self.stack.len() - 4;
+    self.stack[start..].rotate_left(2);
+
+    Ok(())
+  }
+
+  fn c_2over(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 4, op)?;
+
+    let start: usize = self.stack.len() - 4;
+    self.stack.extend_from_within(start..start + 2);
+
+    Ok(())
+  }
+
+  fn c_depth(&mut self, _op: &str) -> Result<(), CompError> {
+    self.stack.push(Value::from(BigInt::from(self.stack.len())));
+
+    Ok(())
+  }
+
+
+  // ---- memory usage ---------------------------------------------------------
+
+  fn c_store_a(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    self.mem_a = self.pop_stack()?;
+
+    Ok(())
+  }
+
+  fn c_push_a(&mut self, _op: &str) -> Result<(), CompError> {
+    self.stack.push(self.mem_a.clone());
+
+    Ok(())
+  }
+
+  fn c_store_b(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    self.mem_b = self.pop_stack()?;
+
+    Ok(())
+  }
+
+  fn c_push_b(&mut self, _op: &str) -> Result<(), CompError> {
+    self.stack.push(self.mem_b.clone());
+
+    Ok(())
+  }
+
+  fn c_store_c(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    self.mem_c = self.pop_stack()?;
+
+    Ok(())
+  }
+
+  fn c_push_c(&mut self, _op: &str) -> Result<(), CompError> {
+    self.stack.push(self.mem_c.clone());
+
+    Ok(())
+  }
+
+  fn c_store(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let name: String = self.pop_argument(op)?;
+    let value: Value = self.pop_stack()?;
+
+    // existing local variables are updated (e.g., by a loop body function)
+    match self.local_frame(&name) {
+      Some(i) => {
+        self.frames[i].vars.insert(name, value);
+      },
+      None => {
+        self.save_variable(&name);
+        self.vars.insert(name, value);
+      },
+    }
+
+    Ok(())
+  }
+
+  fn c_local(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let name: String = self.pop_argument(op)?;
+    let value: Value = self.pop_stack()?;
+
+    // variables defined outside of user functions are global
+    match self.frames.last_mut() {
+      Some(frame) => {
+        frame.vars.insert(name, value);
+      },
+      None => {
+        self.save_variable(&name);
+        self.vars.insert(name, value);
+      },
+    }
+
+    Ok(())
+  }
+
+  fn c_recall(&mut self, op: &str) -> Result<(), CompError> {
+    let name: String = self.pop_argument(op)?;
+
+    let value: Value = self.variable(&name).ok_or_else(|| CompError::UnknownVariable(name.clone()))?.clone();
+    self.stack.push(value);
+
+    Ok(())
+  }
+
+  fn c_save(&mut self, op: &str) -> Result<(), CompError> {
+    let path: String = self.pop_argument(op)?;
+
+    self.save_session(&path)
+  }
+
+  fn c_load(&mut self, op: &str) -> Result<(), CompError> {
+    let path: String = self.pop_argument(op)?;
+
+    self.load_session(&path)
+  }
+
+  fn c_wstack(&mut self, op: &str) -> Result<(), CompError> {
+    let path: String = self.pop_argument(op)?;
+
+    write_stack(&path, &self.stack)
+  }
+
+  fn c_rstack(&mut self, op: &str) -> Result<(), CompError> {
+    let path: String = self.pop_argument(op)?;
+
+    let values: Vec<Value> = read_stack(&path)?;
+    self.stack.extend(values);
+
+    Ok(())
+  }
+
+  fn c_include(&mut self, op: &str) -> Result<(), CompError> {
+    let token: Token = self.ops.pop_front().ok_or_else(|| CompError::MissingArgument(op.to_string()))?;
+
+    // relative paths are relative to the directory of the including file
+    let path: String = match &token.span.source {
+      Some(source) => Path::new(source).parent().unwrap_or(Path::new("")).join(&token.text).display().to_string(),
+      None => token.text,
+    };
+
+    // a file cannot include itself (also through other files) before the
+    // operations following it are processed
+    let pending: usize = self.ops.len();
+    self.includes.retain(|(_, end)| pending >= *end);
+    if self.includes.iter().any(|(included, _)| *included == path) {
+      return Err(CompError::FileError { path, message: "file includes itself".to_string() });
+    }
+
+    let contents: String = fs::read_to_string(&path)
+      .map_err(|error| CompError::FileError { path: path.clone(), message: error.to_string() })?;
+    self.push_front_ops(Token::tokenize(&contents, Some(&path)));
+    self.includes.push((path, pending));
+
+    Ok(())
+  }
+
+
+  // ---- math operations ------------------------------------------------------
+
+  fn c_add(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    self.binary_op(|a, b| Some(a + b), |a, b| a + b, Some(|a, b| a + b))
+  }
+
+  fn c_add_all(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    while self.stack.len() > 1 {
+      self.c_add(op)?;
+    }
+
+    Ok(())
+  }
+
+  fn c_sub(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    self.binary_op(|a, b| Some(a - b), |a, b| a - b, Some(|a, b| a - b))
+  }
+
+  fn c_mult(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    self.binary_op(|a, b| Some(a * b), |a, b| a * b, Some(|a, b| a * b))
+  }
+
+  fn c_mult_all(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    while self.stack.len() > 1 {
+      self.c_mult(op)?;
+    }
+
+    Ok(())
+  }
+
+  fn c_sum(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    while self.stack.len() > 1 {
+      self.c_add(op)?;
+    }
+    self.pop_stack_v().map(|sum| self.stack.push(sum))
+  }
+
+  fn c_mean(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let n: usize = self.stack.len();
+    self.c_sum(op)?;
+    self.stack.push(Value::Int(n as i64));
+
+    self.c_div(op)
+  }
+
+  fn c_median(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+    self.check_real(op)?;
+
+    // mean of the two middle elements when the number of elements is even
+    self.stack.sort_by(Interpreter::total_order);
+    let n: usize = self.stack.len();
+    let middle: Vec<Value> = self.stack.drain(..).skip((n - 1) / 2).take(2 - n % 2).collect();
+    self.stack = middle;
+
+    self.c_mean(op)
+  }
+
+  fn c_var(&mut self, op: &str) -> Result<(), CompError> {
+    let var: f64 = self.variance(op, true)?;
+    self.stack.push(Value::Float(var));
+
+    Ok(())
+  }
+
+  fn c_varp(&mut self, op: &str) -> Result<(), CompError> {
+    let var: f64 = self.variance(op, false)?;
+    self.stack.push(Value::Float(var));
+
+    Ok(())
+  }
+
+  fn c_sd(&mut self, op: &str) -> Result<(), CompError> {
+    let var: f64 = self.variance(op, true)?;
+    self.stack.push(Value::Float(var.sqrt()));
+
+    Ok(())
+  }
+
+  fn c_sdp(&mut self, op: &str) -> Result<(), CompError> {
+    let var: f64 = self.variance(op, false)?;
+    self.stack.push(Value::Float(var.sqrt()));
+
+    Ok(())
+  }
+
+  // remove all stack elements and return their sample (divided by n - 1) or
+  // population (divided by n) variance
+  fn variance(&mut self, op: &str, sample: bool) -> Result<f64, CompError> {
+    Interpreter::check_stack_error(self, if sample { 2 } else { 1 }, op)?;
+    self.check_real(op)?;
+
+    let xs: Vec<f64> = self.stack.drain(..).map(|element| element.to_f64()).collect();
+    let n: f64 = xs.len() as f64;
+    let mean: f64 = xs.iter().sum::<f64>() / n;
+    let ss: f64 = xs.iter().map(|x| (x - mean).powi(2)).sum();
+
+    Ok(ss / if sample { n - 1.0 } else { n })
+  }
+
+  fn c_div(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    self.binary_op(|a, b| if b.is_zero() { None } else { Some(a / b) }, |a, b| a / b, Some(|a, b| a / b))
+  }
+
+  fn c_chs(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    self.unary_op(|a| Some(-a), |a| -a, Some(|z| -z))
+  }
+
+  fn c_abs(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    self.unary_op(|a| Some(a.abs()), |a| a.abs(), Some(|z| Complex64::from(z.norm())))
+  }
+
+  fn c_sign(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    self.unary_op(
+      |a| Some(a.signum()),
+      |a| if a == 0.0 { 0.0 } else { a.signum() },
+      Some(|z| z / z.norm()), // direction of a complex value
+    )
+  }
+
+  fn c_round(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack_v()?;
+
+    self.stack.push(self.round_value(&a, 0));
+
+    Ok(())
+  }
+
+  fn c_roundn(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let n: BigInt = self.pop_stack_i()?;
+    let places: i32 = n.to_i32()
+      .filter(|n| n.unsigned_abs() as usize <= MAX_PRECISION)
+      .ok_or_else(|| CompError::InvalidArgument { op: op.to_string(), value: n.to_string() })?;
+    let a: Value = self.pop_stack_v()?;
+
+    self.stack.push(self.round_value(&a, places));
+
+    Ok(())
+  }
+
+  fn c_inv(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    self.unary_op(|a| if a.is_zero() { None } else { Some(a.recip()) }, |a| 1.0 / a, Some(|z| z.inv()))
+  }
+
+  fn c_sqrt(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack_v()?;
+
+    // negative real values have imaginary roots
+    self.stack.push(match a {
+      Value::Complex(z) => Value::from(z.sqrt()),
+      _ if a.to_f64() < 0.0 => Value::from(Complex64::new(a.to_f64(), 0.0).sqrt()),
+      _ => Value::Float(a.to_f64().sqrt()),
+    });
+
+    Ok(())
+  }
+
+  fn c_hypot(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: f64 = self.pop_stack_f()?;
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.hypot(b)));
+
+    Ok(())
+  }
+
+  fn c_throot(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: f64 = self.pop_stack_f()?;
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.powf(1.0/b)));
+
+    Ok(())
+  }
+
+  fn c_cbrt(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.cbrt()));
+
+    Ok(())
+  }
+
+  fn c_nroot(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let n: f64 = self.pop_stack_f()?;
+    let a: f64 = self.pop_stack_f()?;
+
+    let root: f64 = match n {
+      2.0 => a.sqrt(),
+      3.0 => a.cbrt(),
+      _ if a < 0.0 && n.fract() == 0.0 && n % 2.0 != 0.0 => -(-a).powf(1.0/n), // odd root
+      _ => a.powf(1.0/n),
+    };
+    self.stack.push(Value::Float(root));
+
+    Ok(())
+  }
+
+  fn c_proot(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 3, op)?;
+
+    let c: f64 = self.pop_stack_f()?;
+    let b: f64 = self.pop_stack_f()?;
+    let a: f64 = self.pop_stack_f()?;
+
+    if (b*b - 4.0*a*c) < 0.0 {
+      self.stack.push(Value::Float(-b/(2.0*a))); // root_1 real
+      self.stack.push(Value::Float((4.0*a*c-b*b).sqrt()/(2.0*a))); // root_1 imag
+      self.stack.push(Value::Float(-b/(2.0*a))); // root_2 real
+      self.stack.push(Value::Float(-(4.0*a*c-b*b).sqrt()/(2.0*a))); // root_2 imag
+    } else {
+      self.stack.push(Value::Float(-b+(b*b-4.0*a*c).sqrt()/(2.0*a))); // root_1 real
+      self.stack.push(Value::Float(0.0)); // root_1 imag
+      self.stack.push(Value::Float(-b-(b*b-4.0*a*c).sqrt()/(2.0*a))); // root_2 real
+      self.stack.push(Value::Float(0.0)); // root_2 imag
+    }
+
+    Ok(())
+  }
+
+  fn c_exp(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    // exact for integer exponents (unless the result is too large)
+    self.binary_op(|a, b| {
+      let exponent: i32 = b.to_integer().to_i32().filter(|_| b.is_integer())?;
+      let bits: u64 = a.numer().bits() + a.denom().bits();
+      if bits.saturating_mul(exponent.unsigned_abs() as u64) > MAX_EXACT_BITS || (exponent < 0 && a.is_zero()) {
+        return None;
+      }
+      Some(a.pow(exponent))
+    }, |a, b| a.powf(b), Some(|a, b| {
+      match b.re.to_i32() {
+        Some(n) if b.im == 0.0 && b.re.fract() == 0.0 => a.powi(n), // integer exponent (repeated multiplication)
+        _ => a.powc(b),
+      }
+    }))
+  }
+
+  fn c_mod(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    self.binary_op(|a, b| if b.is_zero() { None } else { Some(a % b) }, |a, b| a % b, None)
+  }
+
+  fn c_floor_div(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    self.binary_op(|a, b| if b.is_zero() { None } else { Some((a / b).floor()) }, |a, b| (a / b).floor(), None)
+  }
+
+  fn c_divmod(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: Value = self.pop_stack_v()?;
+    let a: Value = self.pop_stack_v()?;
+
+    self.stack.extend([a.clone(), b.clone()]);
+    self.c_floor_div(op)?;
+    // remainder with the sign of the divisor
+    self.stack.extend([a, b]);
+    self.binary_op(
+      |a, b| if b.is_zero() { None } else { Some(a - b * (a / b).floor()) },
+      |a, b| a - b * (a / b).floor(),
+      None,
+    )
+  }
+
+  fn c_pctchg(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    self.binary_op(
+      |a, b| if a.is_zero() { None } else { Some((b - a) / a * BigRational::from_integer(100.into())) },
+      |a, b| (b - a) / a * 100.0,
+      Some(|a, b| (b - a) / a * 100.0),
+    )
+  }
+
+  fn c_fact(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    self.unary_op(|a| {
+      let n: u64 = a.to_integer().to_u64().filter(|n| a.is_integer() && *n <= MAX_EXACT_FACTORIAL)?;
+      Some(BigRational::from_integer((2..=n).map(BigInt::from).product()))
+    }, Interpreter::factorial, None)
+  }
+
+  fn c_ncr(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let r: BigInt = self.pop_stack_n()?;
+    let n: BigInt = self.pop_stack_n()?;
+
+    if r > n {
+      self.stack.push(Value::Int(0));
+      return Ok(());
+    }
+    let k: BigInt = (&n - &r).min(r);
+    self.stack.push(match k.to_u64().filter(|k| *k <= MAX_EXACT_FACTORIAL) {
+      Some(k) => {
+        // multiply and divide alternately (each partial result is C(n-k+i, i))
+        let mut c: BigInt = BigInt::one();
+        for i in 1..=k {
+          c = c * (&n - k + i) / i;
+        }
+        Value::from(c)
+      },
+      None => {
+        let (n, k) = (n.to_f64().unwrap_or(f64::INFINITY), k.to_f64().unwrap_or(f64::INFINITY));
+        Value::Float((Interpreter::lngamma(n + 1.0) - Interpreter::lngamma(k + 1.0) - Interpreter::lngamma(n - k + 1.0)).exp())
+      },
+    });
+
+    Ok(())
+  }
+
+  fn c_npr(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let r: BigInt = self.pop_stack_n()?;
+    let n: BigInt = self.pop_stack_n()?;
+
+    if r > n {
+      self.stack.push(Value::Int(0));
+      return Ok(());
+    }
+    self.stack.push(match r.to_u64().filter(|r| *r <= MAX_EXACT_FACTORIAL) {
+      // product of the r largest factors of n! (n-r+1 to n)
+      Some(r) => Value::from((0..r).map(|i| &n - i).product::<BigInt>()),
+      None => {
+        let (n, r) = (n.to_f64().unwrap_or(f64::INFINITY), r.to_f64().unwrap_or(f64::INFINITY));
+        Value::Float((Interpreter::lngamma(n + 1.0) - Interpreter::lngamma(n - r + 1.0)).exp())
+      },
+    });
+
+    Ok(())
+  }
+
+  fn c_gamma(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(Interpreter::gamma(a)));
+
+    Ok(())
+  }
+
+  fn c_lngamma(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(Interpreter::lngamma(a)));
+
+    Ok(())
+  }
+
+  fn c_gcd(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: BigInt = self.pop_stack_n()?;
+    let a: BigInt = self.pop_stack_n()?;
+
+    let gcd: BigInt = match (a.to_u64(), b.to_u64()) {
+      (Some(a), Some(b)) => BigInt::from(Interpreter::gcd(a, b)),
+      _ => a.gcd(&b),
+    };
+
+    self.stack.push(Value::from(gcd));
+
+    Ok(())
+  }
+
+  fn c_lcm(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: BigInt = self.pop_stack_n()?;
+    let a: BigInt = self.pop_stack_n()?;
+
+    // divide before multiplying to keep the intermediate result small
+    self.stack.push(Value::from(a.lcm(&b)));
+
+    Ok(())
+  }
+
+  fn c_modpow(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 3, op)?;
+
+    let m: BigInt = self.pop_stack_n()?;
+    let e: BigInt = self.pop_stack_n()?;
+    let b: BigInt = self.pop_stack_i()?;
+
+    if m.is_zero() {
+      return Err(CompError::InvalidArgument { op: op.to_string(), value: m.to_string() });
+    }
+    self.stack.push(Value::from(b.modpow(&e, &m)));
+
+    Ok(())
+  }
+
+  fn c_modinv(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let m: BigInt = self.pop_stack_n()?;
+    let a: BigInt = self.pop_stack_i()?;
+
+    if m.is_zero() {
+      return Err(CompError::InvalidArgument { op: op.to_string(), value: m.to_string() });
+    }
+    // extended Euclid: a*x + m*y = gcd(a, m)
+    let egcd = a.extended_gcd(&m);
+    if !egcd.gcd.is_one() {
+      return Err(CompError::NoInverse { value: a.to_string(), modulus: m.to_string() });
+    }
+    self.stack.push(Value::from(egcd.x.mod_floor(&m)));
+
+    Ok(())
+  }
+
+  fn c_isprime(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let n: BigInt = self.pop_stack_i()?;
+
+    self.stack.push(Value::Int(Interpreter::is_prime(&n) as i64));
+
+    Ok(())
+  }
+
+  fn c_nextprime(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let mut p: BigInt = self.pop_stack_i()?.max(BigInt::one()) + 1;
+    while !Interpreter::is_prime(&p) {
+      p += 1;
+    }
+    self.stack.push(Value::from(p));
+
+    Ok(())
+  }
+
+  fn c_prevprime(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let n: BigInt = self.pop_stack_i()?;
+    if n <= BigInt::from(2) {
+      return Err(CompError::InvalidArgument { op: op.to_string(), value: n.to_string() });
+    }
+
+    let mut p: BigInt = n - 1;
+    while !Interpreter::is_prime(&p) {
+      p -= 1;
+    }
+    self.stack.push(Value::from(p));
+
+    Ok(())
+  }
+
+  fn c_factor(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack()?;
+    let n: u64 = a.to_u64()
+      .filter(|n| *n >= 2)
+      .ok_or_else(|| CompError::InvalidArgument { op: op.to_string(), value: a.to_string() })?;
+
+    let mut factors: Vec<u64> = Vec::new();
+    Interpreter::factorize(n, &mut factors);
+    factors.sort_unstable();
+    for factor in factors {
+      self.stack.push(Value::from(BigInt::from(factor)));
+    }
+
+    Ok(())
+  }
+
+  fn c_fib(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let n: BigInt = self.pop_stack_n()?;
+
+    self.stack.push(match Interpreter::fibonacci_index(&n) {
+      Some(n) => Value::from(Interpreter::fibonacci(n).0),
+      None => Value::Float(f64::INFINITY),
+    });
+
+    Ok(())
+  }
+
+  fn c_lucas(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let n: BigInt = self.pop_stack_n()?;
+
+    self.stack.push(match Interpreter::fibonacci_index(&n) {
+      Some(n) => { // L(n) = 2F(n+1) - F(n)
+        let (f, g) = Interpreter::fibonacci(n);
+        Value::from(g * 2 - f)
+      },
+      None => Value::Float(f64::INFINITY),
+    });
+
+    Ok(())
+  }
+
+  fn c_min(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: Value = self.pop_stack_v()?;
+    let a: Value = self.pop_stack_v()?;
+
+    self.stack.push(match Interpreter::compare(&a, &b) {
+      Some(Ordering::Greater) => b,
+      Some(_) => a,
+      None => Value::Float(f64::NAN),
+    });
+
+    Ok(())
+  }
+
+  fn c_max(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: Value = self.pop_stack_v()?;
+    let a: Value = self.pop_stack_v()?;
+
+    self.stack.push(match Interpreter::compare(&a, &b) {
+      Some(Ordering::Less) => b,
+      Some(_) => a,
+      None => Value::Float(f64::NAN),
+    });
+
+    Ok(())
+  }
+
+  fn c_min_all(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    while self.stack.len() > 1 {
+      self.c_min(op)?;
+    }
+
+    Ok(())
+  }
+
+  fn c_max_all(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    while self.stack.len() > 1 {
+      self.c_max(op)?;
+    }
+
+    Ok(())
+  }
+
+  fn c_clamp(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 3, op)?;
+
+    let hi: Value = self.pop_stack_v()?;
+    let lo: Value = self.pop_stack_v()?;
+    let a: Value = self.pop_stack_v()?;
+
+    if Interpreter::compare(&lo, &hi) == Some(Ordering::Greater) {
+      return Err(CompError::InvalidArgument { op: op.to_string(), value: format!("{lo} {hi}") });
+    }
+    self.stack.push(match (Interpreter::compare(&a, &lo), Interpreter::compare(&a, &hi)) {
+      (Some(Ordering::Less), _) => lo,
+      (_, Some(Ordering::Greater)) => hi,
+      (Some(_), Some(_)) => a,
+      _ => Value::Float(f64::NAN),
+    });
+
+    Ok(())
+  }
+
+  fn c_lerp(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 3, op)?;
+
+    let t: f64 = self.pop_stack_f()?;
+    let b: f64 = self.pop_stack_f()?;
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a + (b - a) * t));
+
+    Ok(())
+  }
+
+  fn c_invlerp(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 3, op)?;
+
+    let v: f64 = self.pop_stack_f()?;
+    let b: f64 = self.pop_stack_f()?;
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float((v - a) / (b - a)));
+
+    Ok(())
+  }
+
+  fn c_remap(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 5, op)?;
+
+    let d: f64 = self.pop_stack_f()?;
+    let c: f64 = self.pop_stack_f()?;
+    let b: f64 = self.pop_stack_f()?;
+    let a: f64 = self.pop_stack_f()?;
+    let v: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(c + (d - c) * ((v - a) / (b - a))));
+
+    Ok(())
+  }
+
+  fn c_pi(&mut self, _op: &str) -> Result<(), CompError> {
+    self.stack.push(Value::Float(std::f64::consts::PI));
+
+    Ok(())
+  }
+
+  fn c_euler(&mut self, _op: &str) -> Result<(), CompError> {
+    self.stack.push(Value::Float(std::f64::consts::E));
+
+    Ok(())
+  }
+
+  fn c_dtor(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.to_radians()));
+
+    Ok(())
+  }
+
+  fn c_rtod(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.to_degrees()));
+
+    Ok(())
+  }
+
+  fn c_dms(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    // split whole seconds to avoid accumulating rounding errors
+    let seconds: f64 = a.abs() * 3600.0;
+    let d: f64 = (seconds / 3600.0).floor();
+    let m: f64 = ((seconds - d * 3600.0) / 60.0).floor();
+    let s: f64 = seconds - d * 3600.0 - m * 60.0;
+
+    // sign is carried by the first non-zero part
+    let sign: f64 = a.signum();
+    let (d, m, s) = if d != 0.0 {
+      (sign * d, m, s)
+    } else if m != 0.0 {
+      (d, sign * m, s)
+    } else {
+      (d, m, sign * s)
+    };
+    self.stack.push(Value::Float(d));
+    self.stack.push(Value::Float(m));
+    self.stack.push(Value::Float(s));
+
+    Ok(())
+  }
+
+  fn c_ddeg(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 3, op)?;
+
+    let s: f64 = self.pop_stack_f()?;
+    let m: f64 = self.pop_stack_f()?;
+    let d: f64 = self.pop_stack_f()?;
+
+    let a: f64 = d.abs() + m.abs() / 60.0 + s.abs() / 3600.0;
+    let negative: bool = d < 0.0 || m < 0.0 || s < 0.0;
+    self.stack.push(Value::Float(if negative { -a } else { a }));
+
+    Ok(())
+  }
+
+  fn c_sin(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(self.angle_in(a).sin()));
+
+    Ok(())
+  }
+
+  fn c_asin(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(self.angle_out(a.asin())));
+
+    Ok(())
+  }
+
+  fn c_cos(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(self.angle_in(a).cos()));
+
+    Ok(())
+  }
+
+  fn c_acos(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(self.angle_out(a.acos())));
+
+    Ok(())
+  }
+
+  fn c_tan(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(self.angle_in(a).tan()));
+
+    Ok(())
+  }
+
+  fn c_atan(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(self.angle_out(a.atan())));
+
+    Ok(())
+  }
+
+  fn c_atan2(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let x: f64 = self.pop_stack_f()?;
+    let y: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(self.angle_out(y.atan2(x))));
+
+    Ok(())
+  }
+
+  fn c_sinh(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.sinh()));
+
+    Ok(())
+  }
+
+  fn c_cosh(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.cosh()));
+
+    Ok(())
+  }
+
+  fn c_tanh(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.tanh()));
+
+    Ok(())
+  }
+
+  fn c_asinh(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.asinh()));
+
+    Ok(())
+  }
+
+  fn c_acosh(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.acosh()));
+
+    Ok(())
+  }
+
+  fn c_atanh(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.atanh()));
+
+    Ok(())
+  }
+
+  fn c_log10(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.log10()));
+
+    Ok(())
+  }
+
+  fn c_log2(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.log2()));
+
+    Ok(())
+  }
+
+  fn c_logn(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let b: f64 = self.pop_stack_f()?;
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.log(b)));
+
+    Ok(())
+  }
+
+  fn c_ln(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack_v()?;
+
+    self.stack.push(match a {
+      Value::Complex(z) => Value::from(z.ln()),
+      _ => Value::Float(a.to_f64().ln()),
+    });
+
+    Ok(())
+  }
+
+  fn c_exp_e(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack_v()?;
+
+    self.stack.push(match a {
+      Value::Complex(z) => Value::from(z.exp()),
+      _ => Value::Float(a.to_f64().exp()),
+    });
+
+    Ok(())
+  }
+
+  fn c_exp2(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.exp2()));
+
+    Ok(())
+  }
+
+  fn c_expm1(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.exp_m1()));
+
+    Ok(())
+  }
+
+  fn c_ln1p(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a.ln_1p()));
+
+    Ok(())
+  }
+
+
+  fn c_re(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack_v()?;
+
+    self.stack.push(match a {
+      Value::Complex(z) => Value::Float(z.re),
+      _ => a,
+    });
+
+    Ok(())
+  }
+
+  fn c_im(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack_v()?;
+
+    self.stack.push(match a {
+      Value::Complex(z) => Value::Float(z.im),
+      _ => Value::Int(0),
+    });
+
+    Ok(())
+  }
+
+  fn c_conj(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack_v()?;
+
+    self.stack.push(match a {
+      Value::Complex(z) => Value::Complex(z.conj()),
+      _ => a,
+    });
+
+    Ok(())
+  }
+
+  fn c_arg(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Complex64 = self.pop_stack_v()?.to_complex();
+
+    self.stack.push(Value::Float(self.angle_out(a.arg())));
+
+    Ok(())
+  }
+
+  fn c_mag(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Complex64 = self.pop_stack_v()?.to_complex();
+
+    self.stack.push(Value::Float(a.norm()));
+
+    Ok(())
+  }
+
+  fn c_tofloat(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(a));
+
+    Ok(())
+  }
+
+  fn c_tofrac(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let tol: f64 = self.pop_stack_f()?;
+    let a: Value = self.pop_stack_v()?;
+
+    let x: Option<BigRational> = a.to_rational().or_else(|| BigRational::from_float(a.to_f64()));
+    match x {
+      Some(x) => {
+        let tol: BigRational = BigRational::from_float(tol.abs()).unwrap_or_else(BigRational::zero);
+        let r: BigRational = Interpreter::simplest_rational(&x - &tol, &x + &tol);
+        self.stack.push(Value::from(r.numer().clone()));
+        self.stack.push(Value::from(r.denom().clone()));
+      },
+      None => { // NaN or infinite
+        self.stack.push(Value::Float(a.to_f64()));
+        self.stack.push(Value::Int(1));
+      },
+    }
+
+    Ok(())
+  }
+
+  fn c_roman(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack()?;
+    let n: u32 = a.to_integer()
+      .and_then(|i| i.to_u32())
+      .filter(|n| (1..=3999).contains(n))
+      .ok_or_else(|| CompError::InvalidArgument { op: op.to_string(), value: a.to_string() })?;
+
+    self.stack.push(Value::Str(Interpreter::roman(n)));
+
+    Ok(())
+  }
+
+  fn c_unroman(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack()?;
+    let text: String = match &a {
+      Value::Str(text) => text.to_uppercase(),
+      _ => String::new(),
+    };
+
+    let mut n: u32 = 0;
+    let mut rest: &str = &text;
+    for (value, symbol) in ROMAN_NUMERALS {
+      while let Some(tail) = rest.strip_prefix(symbol) {
+        n += value;
+        rest = tail;
+      }
+    }
+    // only numerals in canonical form are accepted (e.g., IV but not IIII)
+    if n == 0 || !rest.is_empty() || Interpreter::roman(n) != text {
+      return Err(CompError::InvalidArgument { op: op.to_string(), value: a.to_string() });
+    }
+
+    self.stack.push(Value::Int(n as i64));
+
+    Ok(())
+  }
+
+  fn c_chr(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack()?;
+    let c: char = a.to_integer()
+      .and_then(|i| i.to_u32())
+      .and_then(char::from_u32)
+      .ok_or_else(|| CompError::InvalidArgument { op: op.to_string(), value: a.to_string() })?;
+
+    self.stack.push(Value::Str(c.to_string()));
+
+    Ok(())
+  }
+
+  fn c_ord(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack()?;
+    let mut chars = match &a {
+      Value::Str(text) => text.chars(),
+      _ => "".chars(),
+    };
+    let c: char = match (chars.next(), chars.next()) {
+      (Some(c), None) => c,
+      _ => return Err(CompError::InvalidArgument { op: op.to_string(), value: a.to_string() }),
+    };
+
+    self.stack.push(Value::Int(c as i64));
+
+    Ok(())
+  }
+
+  fn c_bits(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::from(BigInt::from(a.to_bits())));
+
+    Ok(())
+  }
+
+  fn c_frombits(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack()?;
+    let bits: u64 = a.to_bigint().and_then(|i| i.to_u64()).ok_or_else(|| CompError::NotAnInteger(a.to_string()))?;
+
+    self.stack.push(Value::Float(f64::from_bits(bits)));
+
+    Ok(())
+  }
+
+  fn c_mantissa(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(Interpreter::frexp(a).0));
+
+    Ok(())
+  }
+
+  fn c_expnt(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Int(Interpreter::frexp(a).1 as i64));
+
+    Ok(())
+  }
+
+  fn c_ulp(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: f64 = self.pop_stack_f()?.abs();
+
+    self.stack.push(Value::Float(a.next_up() - a));
+
+    Ok(())
+  }
+
+  fn c_nextafter(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: f64 = self.pop_stack_f()?;
+    let a: f64 = self.pop_stack_f()?;
+
+    self.stack.push(Value::Float(match a.partial_cmp(&b) {
+      Some(std::cmp::Ordering::Less) => a.next_up(),
+      Some(std::cmp::Ordering::Greater) => a.next_down(),
+      Some(std::cmp::Ordering::Equal) => b,
+      None => f64::NAN,
+    }));
+
+    Ok(())
+  }
+
+  fn c_isnan(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack_v()?;
+    let nan: bool = match a {
+      Value::Float(f) => f.is_nan(),
+      Value::Complex(z) => z.is_nan(),
+      _ => false,
+    };
+    self.stack.push(Value::Int(nan as i64));
+
+    Ok(())
+  }
+
+  fn c_isinf(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack_v()?;
+    let inf: bool = match a {
+      Value::Float(f) => f.is_infinite(),
+      Value::Complex(z) => z.is_infinite(),
+      _ => false,
+    };
+    self.stack.push(Value::Int(inf as i64));
+
+    Ok(())
+  }
+
+  fn c_isfinite(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack_v()?;
+    self.stack.push(Value::Int(a.is_finite() as i64));
+
+    Ok(())
+  }
+
+  // ---- bitwise operations ---------------------------------------------------
+
+  fn c_and(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: BigInt = self.pop_stack_i()?;
+    let a: BigInt = self.pop_stack_i()?;
+
+    self.stack.push(Value::from(a & b));
+
+    Ok(())
+  }
+
+  fn c_or(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: BigInt = self.pop_stack_i()?;
+    let a: BigInt = self.pop_stack_i()?;
+
+    self.stack.push(Value::from(a | b));
+
+    Ok(())
+  }
+
+  fn c_xor(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: BigInt = self.pop_stack_i()?;
+    let a: BigInt = self.pop_stack_i()?;
+
+    self.stack.push(Value::from(a ^ b));
+
+    Ok(())
+  }
+
+  fn c_not(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: BigInt = self.pop_stack_i()?;
+
+    self.stack.push(Value::from(!a));
+
+    Ok(())
+  }
+
+  fn c_shl(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let n: u64 = self.pop_shift(op)?;
+    let a: BigInt = self.pop_stack_i()?;
+
+    let shifted: BigInt = a << n;
+    if self.config.integer {
+      self.stack.push(Interpreter::integer_result(Some(BigRational::from_integer(shifted)))?);
+    } else {
+      self.stack.push(Value::from(shifted));
+    }
+
+    Ok(())
+  }
+
+  fn c_shr(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let n: u64 = self.pop_shift(op)?;
+    let a: BigInt = self.pop_stack_i()?;
+
+    self.stack.push(Value::from(a >> n));
+
+    Ok(())
+  }
+
+  // pop shift amount (bits)
+  fn pop_shift(&mut self, op: &str) -> Result<u64, CompError> {
+    let n: BigInt = self.pop_stack_n()?;
+
+    n.to_u64()
+      .filter(|n| *n <= MAX_EXACT_BITS)
+      .ok_or_else(|| CompError::InvalidArgument { op: op.to_string(), value: n.to_string() })
+  }
+
+  // ---- comparison operations ------------------------------------------------
+
+  fn c_lt(&mut self, op: &str) -> Result<(), CompError> {
+    self.compare_op(op, Ordering::is_lt)
+  }
+
+  fn c_gt(&mut self, op: &str) -> Result<(), CompError> {
+    self.compare_op(op, Ordering::is_gt)
+  }
+
+  fn c_le(&mut self, op: &str) -> Result<(), CompError> {
+    self.compare_op(op, Ordering::is_le)
+  }
+
+  fn c_ge(&mut self, op: &str) -> Result<(), CompError> {
+    self.compare_op(op, Ordering::is_ge)
+  }
+
+  fn c_eq(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: Value = self.pop_stack()?;
+    let a: Value = self.pop_stack()?;
+    self.stack.push(Value::Int(Interpreter::equal(&a, &b) as i64));
+
+    Ok(())
+  }
+
+  fn c_ne(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: Value = self.pop_stack()?;
+    let a: Value = self.pop_stack()?;
+    self.stack.push(Value::Int(!Interpreter::equal(&a, &b) as i64));
+
+    Ok(())
+  }
+
+  // pop two numbers and push 1 if their ordering passes the test (0 if the
+  // numbers are unordered, i.e., NaN or complex)
+  fn compare_op(&mut self, op: &str, test: fn(Ordering) -> bool) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: Value = self.pop_stack_v()?;
+    let a: Value = self.pop_stack_v()?;
+    self.stack.push(Value::Int(Interpreter::compare(&a, &b).is_some_and(test) as i64));
+
+    Ok(())
+  }
+
+  // ---- logical operations ---------------------------------------------------
+
+  fn c_land(&mut self, op: &str) -> Result<(), CompError> {
+    self.logical_op(op, |a, b| a && b)
+  }
+
+  fn c_lor(&mut self, op: &str) -> Result<(), CompError> {
+    self.logical_op(op, |a, b| a || b)
+  }
+
+  fn c_lxor(&mut self, op: &str) -> Result<(), CompError> {
+    self.logical_op(op, |a, b| a != b)
+  }
+
+  fn c_lnot(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack()?;
+    self.stack.push(Value::Int(!Interpreter::is_true(&a) as i64));
+
+    Ok(())
+  }
+
+  // pop two conditions and push 1 if the logical operation is true (0
+  // otherwise)
+  fn logical_op(&mut self, op: &str, logical: fn(bool, bool) -> bool) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: Value = self.pop_stack()?;
+    let a: Value = self.pop_stack()?;
+    self.stack.push(Value::Int(logical(Interpreter::is_true(&a), Interpreter::is_true(&b)) as i64));
+
+    Ok(())
+  }
+
+  // ---- random numbers -------------------------------------------------------
+
+  fn c_rand(&mut self, _op: &str) -> Result<(), CompError> {
+    self.stack.push(Value::Float(self.rng.next_f64()));
+
+    Ok(())
+  }
+
+  fn c_randint(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let b: BigInt = self.pop_stack_i()?;
+    let a: BigInt = self.pop_stack_i()?;
+
+    let (a, b) = if a <= b { (a, b) } else { (b, a) };
+    let n: BigInt = match (&b - &a).to_u64() {
+      Some(u64::MAX) => &a + self.rng.next_u64(),
+      Some(span) => &a + self.rng.below(span + 1),
+      None => return Err(CompError::InvalidArgument { op: op.to_string(), value: (b - a).to_string() }),
+    };
+    self.stack.push(Value::from(n));
+
+    Ok(())
+  }
+
+  fn c_randn(&mut self, _op: &str) -> Result<(), CompError> {
+    self.stack.push(Value::Float(self.rng.normal()));
+
+    Ok(())
+  }
+
+  fn c_randexp(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let rate: f64 = self.pop_stack_f()?;
+    if rate.is_nan() || rate <= 0.0 {
+      return Err(CompError::InvalidArgument { op: op.to_string(), value: rate.to_string() });
+    }
+
+    self.stack.push(Value::Float(self.rng.exponential(rate)));
+
+    Ok(())
+  }
+
+  fn c_randpois(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let mean: f64 = self.pop_stack_f()?;
+    if !mean.is_finite() || mean < 0.0 {
+      return Err(CompError::InvalidArgument { op: op.to_string(), value: mean.to_string() });
+    }
+
+    let k: f64 = Interpreter::poisson(&mut self.rng, mean);
+    self.stack.push(Value::from(BigInt::from_f64(k).unwrap_or_default()));
+
+    Ok(())
+  }
+
+  fn c_seed(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack()?;
+    let seed: u64 = a.to_u64()
+      .ok_or_else(|| CompError::InvalidArgument { op: op.to_string(), value: a.to_string() })?;
+
+    self.rng = Rng::new(seed);
+
+    Ok(())
+  }
+
+  fn c_shuffle(&mut self, _op: &str) -> Result<(), CompError> {
+    // Fisher-Yates shuffle
+    for i in (1..self.stack.len()).rev() {
+      let j: usize = self.rng.below(i as u64 + 1) as usize;
+      self.stack.swap(i, j);
+    }
+
+    Ok(())
+  }
+
+  // ---- sequences ------------------------------------------------------------
+
+  fn c_range(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 3, op)?;
+
+    let step: BigInt = self.pop_stack_i()?;
+    let b: BigInt = self.pop_stack_i()?;
+    let a: BigInt = self.pop_stack_i()?;
+
+    if step.is_zero() {
+      return Err(CompError::InvalidArgument { op: op.to_string(), value: step.to_string() });
+    }
+    let count: BigInt = if (step.is_positive() && a <= b) || (step.is_negative() && a >= b) {
+      (&b - &a) / &step + 1
+    } else {
+      BigInt::zero()
+    };
+
+    let mut x: BigInt = a;
+    for _ in 0..self.sequence_length(&count)? {
+      self.stack.push(Value::from(x.clone()));
+      x += &step;
+    }
+
+    Ok(())
+  }
+
+  fn c_linspace(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 3, op)?;
+
+    let n: BigInt = self.pop_stack_n()?;
+    let b: Value = self.pop_stack_v()?;
+    let a: Value = self.pop_stack_v()?;
+
+    if let Value::Complex(_) = a {
+      return Err(CompError::InvalidArgument { op: op.to_string(), value: a.to_string() });
+    }
+    if let Value::Complex(_) = b {
+      return Err(CompError::InvalidArgument { op: op.to_string(), value: b.to_string() });
+    }
+
+    // exact endpoints give exact points (fractions as in the division of the
+    // endpoints)
+    let n: usize = self.sequence_length(&n)?;
+    let intervals: usize = n.saturating_sub(1).max(1);
+    let fraction: bool = self.config.rational || matches!(a, Value::Ratio(_)) || matches!(b, Value::Ratio(_));
+    for i in 0..n {
+      self.stack.push(match (a.to_rational(), b.to_rational()) {
+        (Some(x), Some(y)) => {
+          let r: BigRational = &x + (y - &x) * BigRational::new(BigInt::from(i), BigInt::from(intervals));
+          if fraction || r.is_integer() { Value::from(r) } else { Value::Float(r.to_f64().unwrap_or(f64::NAN)) }
+        },
+        _ if i + 1 == n && n > 1 => b.clone(),
+        _ => Value::Float(a.to_f64() + (b.to_f64() - a.to_f64()) * i as f64 / intervals as f64),
+      });
+    }
+
+    Ok(())
+  }
+
+  // number of elements of a generated sequence (generated elements count
+  // toward the maximum number of operations)
+  fn sequence_length(&mut self, count: &BigInt) -> Result<usize, CompError> {
+    let remaining: usize = self.config.max_ops.saturating_sub(self.steps);
+    match count.to_usize() {
+      Some(n) if n <= remaining => {
+        self.steps += n;
+        Ok(n)
+      },
+      _ => Err(CompError::StepLimit(self.config.max_ops)),
+    }
+  }
+
+  // ---- display --------------------------------------------------------------
+
+  fn c_hex(&mut self, _op: &str) -> Result<(), CompError> {
+    self.config.base = Base::Hex;
+
+    Ok(())
+  }
+
+  fn c_bin(&mut self, _op: &str) -> Result<(), CompError> {
+    self.config.base = Base::Bin;
+
+    Ok(())
+  }
+
+  fn c_oct(&mut self, _op: &str) -> Result<(), CompError> {
+    self.config.base = Base::Oct;
+
+    Ok(())
+  }
+
+  fn c_dec(&mut self, _op: &str) -> Result<(), CompError> {
+    self.config.base = Base::Dec;
+
+    Ok(())
+  }
+
+  fn c_prec(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let a: Value = self.pop_stack()?;
+    let n: BigInt = a.to_integer().ok_or_else(|| CompError::NotAnInteger(a.to_string()))?;
+
+    self.config.precision = if n.is_negative() {
+      None
+    } else {
+      Some(n.to_usize().filter(|n| *n <= MAX_PRECISION).ok_or_else(|| CompError::NotAnInteger(a.to_string()))?)
+    };
+
+    Ok(())
+  }
+
+  fn c_sci(&mut self, _op: &str) -> Result<(), CompError> {
+    self.config.format = Format::Sci;
+
+    Ok(())
+  }
+
+  fn c_fix(&mut self, _op: &str) -> Result<(), CompError> {
+    self.config.format = Format::Fix;
+
+    Ok(())
+  }
+
+  fn c_eng(&mut self, _op: &str) -> Result<(), CompError> {
+    self.config.format = Format::Eng;
+
+    Ok(())
+  }
+
+  fn c_si(&mut self, _op: &str) -> Result<(), CompError> {
+    self.config.format = Format::Si;
+
+    Ok(())
+  }
+
+  fn c_group(&mut self, _op: &str) -> Result<(), CompError> {
+    // group with a point for a decimal comma locale
+    if self.config.group.is_none() {
+      self.config.group = Some(if self.config.decimal_separator() == ',' { '.' } else { ',' });
+    }
+
+    Ok(())
+  }
+
+  fn c_ungroup(&mut self, _op: &str) -> Result<(), CompError> {
+    self.config.group = None;
+
+    Ok(())
+  }
+
+  fn c_auto(&mut self, _op: &str) -> Result<(), CompError> {
+    self.config.format = Format::Auto;
+
+    Ok(())
+  }
+
+  fn c_print(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    self.output.push(self.format_value(&self.stack[self.stack.len() - 1]));
+
+    Ok(())
+  }
+
+  fn c_pstack(&mut self, _op: &str) -> Result<(), CompError> {
+    let lines: Vec<String> = self.stack.iter().map(|element| self.format_value(element)).collect();
+    self.output.extend(lines);
+
+    Ok(())
+  }
+
+  // -- control flow -----------------------------------------------------------
+
+  fn c_fn(&mut self, _op: &str) -> Result<(), CompError> {
+    // get function name
+    let fn_name: String = match self.ops.pop_front() {
+      Some(token) => token.text,
+      None => return Err(CompError::IncompleteFunction(String::new())),
+    };
+
+    // create new function instance and assign function name
+    self.fns.push(Function { name: fn_name,
+                             params: Vec::new(),
+                             fops: Vec::new(),
+                           });
+    let fpos: usize = self.fns.len() - 1; // added function position in function vector
+
+    // build out function operations my reading from interpreter ops
+    loop {
+      match self.ops.pop_front() {
+        Some(fop) if fop.text == "end" => break, // remove "end" op
+        Some(fop) => self.fns[fpos].fops.push(fop),
+        None => {
+          let fn_name: String = self.fns.pop().unwrap().name;
+          return Err(CompError::IncompleteFunction(fn_name));
+        },
+      }
+    }
+
+    // declared parameters precede | (e.g., fn hyp a b | ...)
+    let function: &mut Function = &mut self.fns[fpos];
+    if let Some(bar) = function.fops.iter().position(|fop| fop.text == "|") {
+      let fops: Vec<Token> = function.fops.split_off(bar + 1);
+      function.fops.pop(); // remove "|" op
+      function.params = std::mem::replace(&mut function.fops, fops).into_iter().map(|fop| fop.text).collect();
+    }
+
+    // a new definition replaces an existing function with the same name
+    if let Some(index) = self.fns[..fpos].iter().position(|function| function.name == self.fns[fpos].name) {
+      self.fns.remove(index);
+    }
+
+    Ok(())
+  }
+
+  fn c_alias(&mut self, op: &str) -> Result<(), CompError> {
+    let (name, existing): (String, String) = match (self.ops.pop_front(), self.ops.pop_front()) {
+      (Some(name), Some(existing)) => (name.text, existing.text),
+      _ => return Err(CompError::MissingArgument(op.to_string())),
+    };
+
+    // the new name refers to a copy of the native command or user function
+    // (replacing any command or function with the same name)
+    if let Some(native) = self.cmap.get(&existing).copied() {
+      self.fns.retain(|function| function.name != name);
+      self.cmap.insert(name, native);
+    } else if let Some(index) = self.is_user_function(&existing) {
+      let function: Function = Function { name, ..self.fns[index].clone() };
+      self.cmap.remove(&function.name);
+      self.fns.retain(|other| other.name != function.name);
+      self.fns.push(function);
+    } else {
+      return Err(CompError::UnknownFunction(existing));
+    }
+
+    Ok(())
+  }
+
+  // is operator a user defined function?
+  fn is_user_function(&self, op: &str) -> Option<usize> {
+    if !self.fns.is_empty() {
+      for i in 0..self.fns.len() {
+        if self.fns[i].name == op {
+          return Some(i);
+        }
+      }
+    }
+    None
+  }
+
+  fn c_comment(&mut self, _op: &str) -> Result<(), CompError> {
+    let mut nested: usize = 0;
+
+    while let Some(token) = self.ops.pop_front() {
+      match &token.text[..] {
+        "(" => {
+          nested += 1;
+        },
+        ")" => {
+          if nested == 0 {
+            return Ok(());
+          } else {
+            nested -= 1;
+          }
+        },
+        _ => (),
+      }
+    }
+
+    Ok(())
+  }
+
+
+  fn c_quote(&mut self, _op: &str) -> Result<(), CompError> {
+    let fops: Vec<Token> = self.pop_quotation()?;
+    self.stack.push(Value::Block(fops));
+
+    Ok(())
+  }
+
+  fn c_call(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    // the quotation is called as the next operations (undone as a whole, as
+    // a user function call)
+    match self.pop_stack()? {
+      Value::Block(fops) => {
+        if self.nesting == 0 && self.outer_ops.is_none() {
+          self.outer_ops = Some(self.ops.len());
+        }
+        self.push_front_ops(fops);
+      },
+      element => return Err(CompError::InvalidArgument { op: op.to_string(), value: element.to_string() }),
+    }
+
+    Ok(())
+  }
+
+  fn c_if(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let fops: Vec<Token> = self.pop_function(op)?;
+    let c: Value = self.pop_stack()?;
+
+    // the function is called as the next operation (so that recursive
+    // functions do not nest)
+    if Interpreter::is_true(&c) {
+      self.push_front_ops(fops);
+    }
+
+    Ok(())
+  }
+
+  fn c_ifelse(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let true_fops: Vec<Token> = self.pop_function(op)?;
+    let false_fops: Vec<Token> = self.pop_function(op)?;
+    let c: Value = self.pop_stack()?;
+
+    self.push_front_ops(if Interpreter::is_true(&c) { true_fops } else { false_fops });
+
+    Ok(())
+  }
+
+  fn c_while(&mut self, op: &str) -> Result<(), CompError> {
+    let condition: Vec<Token> = self.pop_function(op)?;
+    let body: Vec<Token> = self.pop_function(op)?;
+
+    // the condition function pushes a condition that is popped before the
+    // body function is called
+    loop {
+      self.call_function(&condition)?;
+      let c: Value = self.pop_stack()?;
+      if !Interpreter::is_true(&c) {
+        break;
+      }
+      self.call_function(&body)?;
+    }
+
+    Ok(())
+  }
+
+  fn c_times(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let fops: Vec<Token> = self.pop_function(op)?;
+    // counts beyond the maximum number of operations end with a step limit
+    // error
+    let n: u64 = self.pop_stack_n()?.to_u64().unwrap_or(u64::MAX);
+
+    for _ in 0..n {
+      self.call_function(&fops)?;
+    }
+
+    Ok(())
+  }
+
+  fn c_for(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 2, op)?;
+
+    let fops: Vec<Token> = self.pop_function(op)?;
+    let b: BigInt = self.pop_stack_i()?;
+    let a: BigInt = self.pop_stack_i()?;
+
+    // the loop index is pushed before each call of the function
+    let mut i: BigInt = a;
+    while i <= b {
+      self.stack.push(Value::from(i.clone()));
+      self.call_function(&fops)?;
+      i += 1;
+    }
+
+    Ok(())
+  }
+
+  fn c_again(&mut self, op: &str) -> Result<(), CompError> {
+    self.repeat_last(op, 1)
+  }
+
+  fn c_redo(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let n: BigInt = self.pop_stack_n()?;
+    let n: usize = self.sequence_length(&n)?;
+
+    self.repeat_last(op, n)
+  }
+
+  fn c_undo(&mut self, op: &str) -> Result<(), CompError> {
+    let snapshot: Snapshot = self.history.pop_back().ok_or_else(|| CompError::NoHistory(op.to_string()))?;
+
+    self.stack.truncate(snapshot.base);
+    self.stack.extend(snapshot.top);
+    self.mem_a = snapshot.mem_a;
+    self.mem_b = snapshot.mem_b;
+    self.mem_c = snapshot.mem_c;
+    for (name, value) in snapshot.vars {
+      match value {
+        Some(value) => self.vars.insert(name, value),
+        None => self.vars.remove(&name),
+      };
+    }
+    self.recording = false;
+
+    Ok(())
+  }
+
+  // repeat last command n times (pushing its repeated operands first)
+  fn repeat_last(&mut self, op: &str, n: usize) -> Result<(), CompError> {
+    let (name, operands) = self.last_op.clone().ok_or_else(|| CompError::NoHistory(op.to_string()))?;
+
+    for _ in 0..n {
+      self.stack.extend(operands.iter().cloned());
+      self.call_function(&[Token::unlocated(&name)])?;
+    }
+
+    Ok(())
+  }
+
+  // can the command be repeated by again (commands that read arguments from
+  // the operations list cannot)
+  fn is_repeatable(op: &str) -> bool {
+    !matches!(op, "again" | "redo" | "fn" | "alias" | "(" | "[" | "save" | "load" | "wstack" | "rstack" | "include" | "store" | "local" | "recall") && Interpreter::function_arguments(op) == 0
+  }
+
+  // number of function arguments that follow the command in the operations
+  // list
+  fn function_arguments(op: &str) -> usize {
+    FUNCTION_COMMANDS.iter().find(|(name, _)| *name == op).map_or(0, |(_, count)| *count)
+  }
+
+  fn c_map(&mut self, op: &str) -> Result<(), CompError> {
+    let fops: Vec<Token> = self.pop_function(op)?;
+
+    // each element is passed to the function on its own stack, and all
+    // results are collected in order (the stack is unchanged on error)
+    let elements: Vec<Value> = std::mem::take(&mut self.stack);
+    let mut results: Vec<Value> = Vec::with_capacity(elements.len());
+    for element in &elements {
+      self.stack = vec![element.clone()];
+      if let Err(error) = self.call_function(&fops) {
+        self.stack = elements;
+        return Err(error);
+      }
+      results.append(&mut self.stack);
+    }
+    self.stack = results;
+
+    Ok(())
+  }
+
+  fn c_filter(&mut self, op: &str) -> Result<(), CompError> {
+    let fops: Vec<Token> = self.pop_function(op)?;
+
+    // elements are kept when the predicate leaves a true value on top of the
+    // element's own stack (the stack is unchanged on error)
+    let elements: Vec<Value> = std::mem::take(&mut self.stack);
+    let mut results: Vec<Value> = Vec::with_capacity(elements.len());
+    for element in &elements {
+      self.stack = vec![element.clone()];
+      if let Err(error) = self.call_function(&fops) {
+        self.stack = elements;
+        return Err(error);
+      }
+      if self.stack.last().is_some_and(Interpreter::is_true) {
+        results.push(element.clone());
+      }
+    }
+    self.stack = results;
+
+    Ok(())
+  }
+
+  fn c_fold(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let fops: Vec<Token> = self.pop_function(op)?;
+
+    self.fold_stack(&fops, false)
+  }
+
+  fn c_reduce(&mut self, op: &str) -> Result<(), CompError> {
+    Interpreter::check_stack_error(self, 1, op)?;
+
+    let fops: Vec<Token> = self.pop_function(op)?;
+
+    self.fold_stack(&fops, true)
+  }
+
+  // replace stack with the accumulated result of applying a two-argument
+  // function to the accumulator (the first or the top element) and each
+  // other element in turn, from bottom to top (the stack is unchanged on
+  // error)
+  fn fold_stack(&mut self, fops: &[Token], first: bool) -> Result<(), CompError> {
+    let original: Vec<Value> = std::mem::take(&mut self.stack);
+    let (init, elements) = if first {
+      (&original[0], &original[1..])
+    } else {
+      (&original[original.len() - 1], &original[..original.len() - 1])
+    };
+
+    let mut acc: Value = init.clone();
+    for element in elements {
+      self.stack = vec![acc, element.clone()];
+      acc = match self.call_function(fops).and_then(|_| self.pop_stack()) {
+        Ok(acc) => acc,
+        Err(error) => {
+          self.stack = original;
+          return Err(error);
+        },
+      };
+    }
+    self.stack = vec![acc];
+
+    Ok(())
+  }
+
+  fn c_zipwith(&mut self, op: &str) -> Result<(), CompError> {
+    let fops: Vec<Token> = self.pop_function(op)?;
+
+    self.zip_stack(op, &fops)
+  }
+
+  fn c_zip_add(&mut self, op: &str) -> Result<(), CompError> {
+    self.zip_stack(op, &[Token::unlocated("+")])
+  }
+
+  fn c_zip_mult(&mut self, op: &str) -> Result<(), CompError> {
+    self.zip_stack(op, &[Token::unlocated("x")])
+  }
+
+  // replace stack with the results of applying a two-argument function to
+  // the corresponding elements of the bottom and the top half of the stack
+  // (the stack is unchanged on error)
+  fn zip_stack(&mut self, op: &str, fops: &[Token]) -> Result<(), CompError> {
+    if !self.stack.len().is_multiple_of(2) {
+      return Err(CompError::UnevenStack { op: op.to_string(), depth: self.stack.len() });
+    }
+
+    let elements: Vec<Value> = std::mem::take(&mut self.stack);
+    let (bottom, top) = elements.split_at(elements.len() / 2);
+    let mut results: Vec<Value> = Vec::with_capacity(bottom.len());
+    for (a, b) in bottom.iter().zip(top) {
+      self.stack = vec![a.clone(), b.clone()];
+      if let Err(error) = self.call_function(fops) {
+        self.stack = elements;
+        return Err(error);
+      }
+      results.append(&mut self.stack);
+    }
+    self.stack = results;
+
+    Ok(())
+  }
+
+  fn c_keep(&mut self, op: &str) -> Result<(), CompError> {
+    let fops: Vec<Token> = self.pop_function(op)?;
+
+    // operands are the arguments of a command with a fixed number of
+    // arguments or of a user function with declared parameters, and the
+    // whole stack otherwise (e.g., of other user functions or quotations)
+    let fixed: Option<(&str, usize)> = match &fops[..] {
+      [fop] => match self.cmap.get(&fop.text) {
+        Some(native) => native.info.arity(),
+        None => self.is_user_function(&fop.text).map(|index| self.fns[index].params.len()).filter(|count| *count > 0),
+      }.map(|arity| (fop.text.as_str(), arity)),
+      _ => None,
+    };
+    let arity: usize = match fixed {
+      Some((name, arity)) => {
+        Interpreter::check_stack_error(self, arity, name)?;
+        arity
+      },
+      None => self.stack.len(),
+    };
+
+    // the function is applied to a copy of its operands, and the results are
+    // pushed after the operands (the stack is unchanged on error)
+    let operands: Vec<Value> = self.stack[self.stack.len() - arity..].to_vec();
+    let elements: Vec<Value> = std::mem::replace(&mut self.stack, operands);
+    if let Err(error) = self.call_function(&fops) {
+      self.stack = elements;
+      return Err(error);
+    }
+    let mut results: Vec<Value> = std::mem::replace(&mut self.stack, elements);
+    self.stack.append(&mut results);
+
+    Ok(())
+  }
+
+  // get function argument as an operations list (a command or user-defined
+  // function name, or the operations of a quotation)
+  fn pop_function(&mut self, op: &str) -> Result<Vec<Token>, CompError> {
+    let token: Token = self.ops.pop_front().ok_or_else(|| CompError::MissingArgument(op.to_string()))?;
+    if token.text == "[" {
+      return self.pop_quotation();
+    }
+    if !self.cmap.contains_key(&token.text) && self.is_user_function(&token.text).is_none() {
+      return Err(CompError::UnknownFunction(token.text));
+    }
+
+    Ok(vec![token])
+  }
+
+  // get the operations of a quotation up to the matching ] (quotations can be
+  // nested)
+  fn pop_quotation(&mut self) -> Result<Vec<Token>, CompError> {
+    let mut fops: Vec<Token> = Vec::new();
+    let mut nested: usize = 0;
+
+    while let Some(token) = self.ops.pop_front() {
+      match &token.text[..] {
+        "[" => nested += 1,
+        "]" if nested == 0 => return Ok(fops),
+        "]" => nested -= 1,
+        _ => (),
+      }
+      fops.push(token);
+    }
+
+    Err(CompError::IncompleteQuotation)
+  }
+
+  // add operations to the front of the operations list (called as the next
+  // operations)
+  fn push_front_ops(&mut self, fops: Vec<Token>) {
+    for fop in fops.into_iter().rev() {
+      self.ops.push_front(fop);
+    }
+  }
+
+  // discard the local variables of user function calls whose operations have
+  // all been processed
+  fn close_frames(&mut self) {
+    while self.frames.last().is_some_and(|frame| frame.nesting == self.nesting && self.ops.len() <= frame.end) {
+      self.frames.pop();
+    }
+  }
+
+  // variable by name (local variables of the current user function call and
+  // of the calls of commands that call functions, e.g., while, then global
+  // variables)
+  fn variable(&self, name: &str) -> Option<&Value> {
+    match self.local_frame(name) {
+      Some(i) => self.frames[i].vars.get(name),
+      None => self.vars.get(name),
+    }
+  }
+
+  // position of the call frame defining a visible local variable
+  fn local_frame(&self, name: &str) -> Option<usize> {
+    // only the innermost call of each nesting level is visible
+    let mut nesting: Option<usize> = None;
+    self.frames.iter().rposition(|frame| {
+      let visible: bool = nesting != Some(frame.nesting);
+      nesting = Some(frame.nesting);
+      visible && frame.vars.contains_key(name)
+    })
+  }
+
+  // evaluate function operations (a command or user-defined function name, or
+  // the operations of a quotation) to completion on the current stack before
+  // the pending operations continue
+  fn call_function(&mut self, fops: &[Token]) -> Result<(), CompError> {
+    let max_nesting: usize = self.config.max_depth.min(MAX_NESTING);
+    if self.nesting >= max_nesting {
+      return Err(CompError::NestingLimit(max_nesting));
+    }
+
+    // the call counts toward the maximum number of operations
+    self.steps += 1;
+    if self.steps > self.config.max_ops {
+      return Err(CompError::StepLimit(self.config.max_ops));
+    }
+
+    let pending: VecDeque<Token> = std::mem::replace(&mut self.ops, fops.iter().cloned().collect());
+    self.nesting += 1;
+
+    // errors of the function operations (which remain at the end of the
+    // operations list) are located at the calling command, and errors of
+    // the operations of called user functions at their source
+    let mut remaining: usize = fops.len();
+    let mut result: Result<(), CompError> = Ok(());
+    while result.is_ok() {
+      self.close_frames();
+      remaining = remaining.min(self.ops.len());
+      let calling: bool = self.ops.len() <= remaining;
+      match self.ops.pop_front() {
+        Some(token) if calling => result = self.process_node(&token.text),
+        Some(token) => result = self.eval_token(&token),
+        None => break,
+      }
+    }
+    while self.frames.last().is_some_and(|frame| frame.nesting == self.nesting) {
+      self.frames.pop();
+    }
+    self.nesting -= 1;
+    self.ops = pending;
+
+    result
+  }
+
+
+  // support functions ---------------------------------------------------------
+
+  // numerical equality of values (text values and quotations are equal to
+  // identical text values and quotations only)
+  fn equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+      (Value::Str(x), Value::Str(y)) => x == y,
+      (Value::Block(x), Value::Block(y)) => x.iter().map(|fop| &fop.text).eq(y.iter().map(|fop| &fop.text)),
+      (Value::Str(_) | Value::Block(_), _) | (_, Value::Str(_) | Value::Block(_)) => false,
+      (Value::Complex(_), _) | (_, Value::Complex(_)) => a.to_complex() == b.to_complex(),
+      _ => Interpreter::compare(a, b) == Some(Ordering::Equal),
+    }
+  }
+
+  // truth value of a condition (zero, NaN, and empty text are false)
+  fn is_true(a: &Value) -> bool {
+    match a {
+      Value::Int(i) => *i != 0,
+      Value::Float(f) => *f != 0.0 && !f.is_nan(),
+      Value::Str(text) => !text.is_empty(),
+      Value::Big(_) | Value::Ratio(_) | Value::Complex(_) | Value::Block(_) => true,
+    }
+  }
+
+  // factorial (gamma function of o+1)
+  fn factorial(o: f64) -> f64 {
+    Interpreter::gamma(o + 1.0)
+  }
+
+  // gamma function (exact product for small positive integers, Lanczos
+  // approximation otherwise, NaN at the poles)
+  fn gamma(x: f64) -> f64 {
+    if x <= 0.0 && x.fract() == 0.0 {
+      return f64::NAN;
+    }
+    if x.fract() == 0.0 && x <= 171.0 {
+      return (2..x as u64).map(|i| i as f64).product();
+    }
+    if x > MAX_GAMMA { // the power and exponential terms would overflow
+      return f64::INFINITY;
+    }
+    if x < 0.5 { // reflection formula
+      return std::f64::consts::PI / ((std::f64::consts::PI * x).sin() * Interpreter::gamma(1.0 - x));
+    }
+
+    let x: f64 = x - 1.0;
+    let t: f64 = x + LANCZOS_G + 0.5;
+    let sum: f64 = Interpreter::lanczos_sum(x);
+    // split the power to avoid intermediate overflow
+    let power: f64 = t.powf((x + 0.5) / 2.0);
+
+    (2.0 * std::f64::consts::PI).sqrt() * power * (-t).exp() * power * sum
+  }
+
+  // natural logarithm of the absolute value of the gamma function
+  fn lngamma(x: f64) -> f64 {
+    if x <= 0.0 && x.fract() == 0.0 {
+      return f64::INFINITY;
+    }
+    if x.fract() == 0.0 && x <= 171.0 {
+      return Interpreter::gamma(x).ln();
+    }
+    if x < 0.5 { // reflection formula
+      return (std::f64::consts::PI / (std::f64::consts::PI * x).sin().abs()).ln() - Interpreter::lngamma(1.0 - x);
+    }
+
+    let x: f64 = x - 1.0;
+    let t: f64 = x + LANCZOS_G + 0.5;
+
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + Interpreter::lanczos_sum(x).ln()
+  }
+
+  // Lanczos series of the gamma function approximation
+  fn lanczos_sum(x: f64) -> f64 {
+    LANCZOS.iter().enumerate().skip(1).fold(LANCZOS[0], |sum, (i, c)| sum + c / (x + i as f64))
+  }
+
+  // significand and binary exponent of a floating point number (a = m*2^e
+  // with 1 <= |m| < 2, or m = a and e = 0 for zero and non-finite numbers)
+  fn frexp(a: f64) -> (f64, i32) {
+    if a == 0.0 || !a.is_finite() {
+      return (a, 0);
+    }
+
+    // scale subnormal numbers into the normal range
+    let (a, offset) = if a.is_subnormal() { (a * 2f64.powi(64), -64) } else { (a, 0) };
+    let bits: u64 = a.to_bits();
+    let exponent: i32 = ((bits >> 52) & 0x7ff) as i32 - 1023;
+    let significand: f64 = f64::from_bits((bits & !(0x7ff << 52)) | (1023 << 52));
+
+    (significand, exponent + offset)
+  }
+
+  // Roman numeral of a positive integer
+  fn roman(mut n: u32) -> String {
+    let mut numeral: String = String::new();
+
+    for (value, symbol) in ROMAN_NUMERALS {
+      while n >= value {
+        numeral.push_str(symbol);
+        n -= value;
+      }
+    }
+
+    numeral
+  }
+
+  // fraction with the smallest denominator in the closed interval [lo, hi]
+  // (continued fraction expansion of the interval endpoints)
+  fn simplest_rational(lo: BigRational, hi: BigRational) -> BigRational {
+    if !lo.is_positive() && !hi.is_negative() {
+      return BigRational::zero();
+    }
+    if hi.is_negative() {
+      return -Interpreter::simplest_rational(-hi, -lo);
+    }
+
+    let mut terms: Vec<BigRational> = Vec::new();
+    let (mut lo, mut hi) = (lo, hi);
+    loop {
+      let ceil: BigRational = lo.ceil();
+      if ceil <= hi {
+        terms.push(ceil);
+        break;
+      }
+      let floor: BigRational = lo.floor();
+      (lo, hi) = ((&hi - &floor).recip(), (&lo - &floor).recip());
+      terms.push(floor);
+    }
+
+    let mut r: BigRational = terms.pop().unwrap();
+    while let Some(term) = terms.pop() {
+      r = term + r.recip();
+    }
+
+    r
+  }
+
+  // order of two values (exact when both values are exact, `None` when
+  // either value is NaN or complex)
+  fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    if let (Value::Complex(_), _) | (_, Value::Complex(_)) = (a, b) {
+      return None;
+    }
+
+    match (a.to_rational(), b.to_rational()) {
+      (Some(x), Some(y)) => Some(x.cmp(&y)),
+      _ => a.to_f64().partial_cmp(&b.to_f64()),
+    }
+  }
+
+  // Miller-Rabin primality test (deterministic below 3.3e24, probable prime
+  // above)
+  fn is_prime(n: &BigInt) -> bool {
+    if *n < BigInt::from(2) {
+      return false;
+    }
+    for p in PRIME_WITNESSES {
+      if *n == BigInt::from(p) {
+        return true;
+      }
+      if (n % p).is_zero() {
+        return false;
+      }
+    }
+
+    // n - 1 = d*2^s with d odd
+    let n1: BigInt = n - 1;
+    let s: u64 = n1.trailing_zeros().unwrap_or(0);
+    let d: BigInt = &n1 >> s;
+    'witness: for a in PRIME_WITNESSES {
+      let mut x: BigInt = BigInt::from(a).modpow(&d, n);
+      if x.is_one() || x == n1 {
+        continue;
+      }
+      for _ in 1..s {
+        x = &x * &x % n;
+        if x == n1 {
+          continue 'witness;
+        }
+      }
+      return false;
+    }
+
+    true
+  }
+
+  // prime factors of n (unordered, with multiplicity)
+  fn factorize(n: u64, factors: &mut Vec<u64>) {
+    if n == 1 {
+      return;
+    }
+    if Interpreter::is_prime(&BigInt::from(n)) {
+      factors.push(n);
+      return;
+    }
+    for p in [2, 3, 5] {
+      if n.is_multiple_of(p) {
+        factors.push(p);
+        return Interpreter::factorize(n / p, factors);
+      }
+    }
+
+    let d: u64 = Interpreter::pollard_rho(n);
+    Interpreter::factorize(d, factors);
+    Interpreter::factorize(n / d, factors);
+  }
+
+  // non-trivial divisor of an odd composite number (Pollard's rho method)
+  fn pollard_rho(n: u64) -> u64 {
+    let step = |x: u64, c: u64| ((x as u128 * x as u128 + c as u128) % n as u128) as u64;
+
+    for c in 1.. {
+      let (mut x, mut y, mut d) = (2, 2, 1);
+      while d == 1 {
+        x = step(x, c);
+        y = step(step(y, c), c);
+        d = Interpreter::gcd(x.abs_diff(y), n);
+      }
+      if d != n {
+        return d;
+      }
+    }
+
+    n
+  }
+
+  // index of a Fibonacci number within the exact integer size limit (F(n)
+  // has about 0.694n bits)
+  fn fibonacci_index(n: &BigInt) -> Option<u64> {
+    n.to_u64().filter(|n| *n <= MAX_EXACT_BITS * 3 / 2)
+  }
+
+  // Fibonacci numbers F(n) and F(n+1) (fast doubling)
+  fn fibonacci(n: u64) -> (BigInt, BigInt) {
+    if n == 0 {
+      return (BigInt::zero(), BigInt::one());
+    }
+
+    let (a, b) = Interpreter::fibonacci(n / 2);
+    let c: BigInt = &a * (&b * 2 - &a); // F(2k)
+    let d: BigInt = &a * &a + &b * &b;  // F(2k+1)
+    if n.is_multiple_of(2) {
+      (c, d)
+    } else {
+      let e: BigInt = &c + &d;
+      (d, e)
+    }
+  }
+
+  // Poisson random number (Knuth's multiplication method for small means,
+  // Hormann's transformed rejection method for large means)
+  fn poisson(rng: &mut Rng, mean: f64) -> f64 {
+    if mean < 30.0 {
+      let limit: f64 = (-mean).exp();
+      let (mut k, mut p) = (0.0, rng.next_f64());
+      while p > limit {
+        k += 1.0;
+        p *= rng.next_f64();
+      }
+      return k;
+    }
+
+    let b: f64 = 0.931 + 2.53 * mean.sqrt();
+    let a: f64 = -0.059 + 0.02483 * b;
+    let alpha: f64 = 1.1239 + 1.1328 / (b - 3.4);
+    let vr: f64 = 0.9277 - 3.6224 / (b - 2.0);
+    loop {
+      let u: f64 = rng.next_f64() - 0.5;
+      let v: f64 = rng.next_f64();
+      let us: f64 = 0.5 - u.abs();
+      let k: f64 = ((2.0 * a / us + b) * u + mean + 0.43).floor();
+      if us >= 0.07 && v <= vr {
+        return k;
+      }
+      if k < 0.0 || (us < 0.013 && v > us) {
+        continue;
+      }
+      if v.ln() + alpha.ln() - (a / (us * us) + b).ln() <= -mean + k * mean.ln() - Interpreter::lngamma(k + 1.0) {
+        return k;
+      }
+    }
+  }
+
+  // total order of real values (NaN is greater than all other values)
+  fn total_order(a: &Value, b: &Value) -> Ordering {
+    Interpreter::compare(a, b).unwrap_or_else(|| a.to_f64().total_cmp(&b.to_f64()))
+  }
+
+  // greatest common divisor
+  fn gcd(a: u64, b: u64) -> u64 {
+    if b != 0 {
+      Interpreter::gcd(b, a % b)
+    } else {
+      a
+    }
+  }
+
+}
+
+impl Default for Interpreter {
+  fn default() -> Self {
+    Interpreter::new()
+  }
+}
+
+
+#[cfg(test)]
+#[path = "./comp_test.rs"]
+mod comp_test;
@@ -0,0 +1,37 @@
+//! comp - reverse Polish notation (RPN) command interpreter
+//!
+//! The interpreter evaluates lists of postfix operations, where each
+//! operation is either a command (symbol) or a value to be pushed onto the
+//! stack. The engine can be embedded in other Rust programs without going
+//! through the `comp` command line interface.
+//!
+//! ```
+//! use comp::Interpreter;
+//!
+//! let mut cinter = Interpreter::new();
+//! cinter.push_ops("3 dup x 4 dup x +");
+//! cinter.process_ops().unwrap();
+//!
+//! assert_eq!(cinter.stack[0].to_string(), "25");
+//! ```
+
+mod config;
+mod error;
+mod interpreter;
+mod random;
+mod session;
+mod token;
+mod value;
+
+pub use config::AngleMode;
+pub use config::Base;
+pub use config::ColorMode;
+pub use config::Config;
+pub use config::Format;
+pub use config::Rounding;
+pub use error::CompError;
+pub use interpreter::CommandInfo;
+pub use interpreter::Interpreter;
+pub use token::Span;
+pub use token::Token;
+pub use value::Value;
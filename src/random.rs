@@ -0,0 +1,62 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+// pseudo-random number generator (SplitMix64). not suitable for
+// cryptographic use
+#[derive(Debug, Clone)]
+pub(crate) struct Rng {
+  state: u64,
+}
+
+impl Rng {
+  // generator with the given seed (same sequence for the same seed)
+  pub fn new(seed: u64) -> Rng {
+    Rng { state: seed }
+  }
+
+  // generator seeded from the randomly keyed standard library hasher
+  pub fn from_entropy() -> Rng {
+    Rng::new(RandomState::new().build_hasher().finish())
+  }
+
+  // uniformly distributed 64-bit integer
+  pub fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z: u64 = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+    z ^ (z >> 31)
+  }
+
+  // uniformly distributed floating point number in [0, 1)
+  pub fn next_f64(&mut self) -> f64 {
+    (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+  }
+
+  // standard normal random number (Box-Muller transform)
+  pub fn normal(&mut self) -> f64 {
+    let u: f64 = 1.0 - self.next_f64(); // (0, 1] avoids ln(0)
+    let v: f64 = self.next_f64();
+
+    (-2.0 * u.ln()).sqrt() * (2.0 * std::f64::consts::PI * v).cos()
+  }
+
+  // exponential random number with the given rate (inverse transform)
+  pub fn exponential(&mut self, rate: f64) -> f64 {
+    -(1.0 - self.next_f64()).ln() / rate
+  }
+
+  // uniformly distributed integer in [0, n) (rejection sampling avoids the
+  // bias of a plain modulus)
+  pub fn below(&mut self, n: u64) -> u64 {
+    let zone: u64 = u64::MAX - u64::MAX % n;
+
+    loop {
+      let x: u64 = self.next_u64();
+      if x < zone {
+        return x % n;
+      }
+    }
+  }
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::fs;
+use serde::{Deserialize, Serialize};
+use crate::error::CompError;
+use crate::interpreter::Function;
+use crate::value::Value;
+
+// serialized interpreter state (stack, memory registers, variables, and
+// user-defined functions)
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Session {
+  pub stack: Vec<Value>,
+  pub mem_a: Value,
+  pub mem_b: Value,
+  pub mem_c: Value,
+  #[serde(default)] // sessions saved before named variables
+  pub vars: HashMap<String, Value>,
+  pub fns: Vec<Function>,
+}
+
+impl Session {
+  // read session from file
+  pub fn read(path: &str) -> Result<Session, CompError> {
+    let contents: String = fs::read_to_string(path)
+      .map_err(|error| CompError::FileError { path: path.to_string(), message: error.to_string() })?;
+
+    serde_json::from_str(&contents)
+      .map_err(|error| CompError::FileError { path: path.to_string(), message: error.to_string() })
+  }
+
+  // write session to file
+  pub fn write(&self, path: &str) -> Result<(), CompError> {
+    let contents: String = serde_json::to_string_pretty(self)
+      .map_err(|error| CompError::FileError { path: path.to_string(), message: error.to_string() })?;
+
+    fs::write(path, contents)
+      .map_err(|error| CompError::FileError { path: path.to_string(), message: error.to_string() })
+  }
+}
+
+// write stack to a text file (one value per line, as it would be entered)
+pub(crate) fn write_stack(path: &str, stack: &[Value]) -> Result<(), CompError> {
+  let mut contents: String = String::new();
+  for element in stack {
+    let literal: String = match element {
+      Value::Float(f) => format!("{f:?}"), // keep floating point type (e.g., 3.0)
+      Value::Str(text) => format!("\"{text}\""),
+      Value::Block(_) => {
+        let message: String = format!("quotation {element} cannot be written as a value");
+        return Err(CompError::FileError { path: path.to_string(), message });
+      },
+      _ => element.to_string(),
+    };
+    contents.push_str(&literal);
+    contents.push('\n');
+  }
+
+  fs::write(path, contents)
+    .map_err(|error| CompError::FileError { path: path.to_string(), message: error.to_string() })
+}
+
+// read stack from a text file written by write_stack (whitespace separated
+// values)
+pub(crate) fn read_stack(path: &str) -> Result<Vec<Value>, CompError> {
+  let contents: String = fs::read_to_string(path)
+    .map_err(|error| CompError::FileError { path: path.to_string(), message: error.to_string() })?;
+
+  contents.split_whitespace()
+    .map(|op| Value::parse(op)
+      .ok_or_else(|| CompError::FileError { path: path.to_string(), message: format!("invalid value [{op}]") }))
+    .collect()
+}
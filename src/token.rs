@@ -0,0 +1,63 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Source location of an operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Span {
+  /// Position of the operation in its operations list (starting at 1).
+  pub index: usize,
+  /// Name of the source file the operation was read from.
+  pub source: Option<String>,
+  /// Line number of the operation (for operations read from text).
+  pub line: Option<usize>,
+}
+
+/// Operation together with the location it was read from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Token {
+  pub text: String,
+  pub span: Span,
+}
+
+impl Token {
+  // operation without a source location (e.g., a command called by another
+  // command)
+  pub(crate) fn unlocated(text: &str) -> Token {
+    Token {
+      text: text.to_string(),
+      span: Span { index: 0, source: None, line: None },
+    }
+  }
+
+  /// Split whitespace-separated operations into tokens, tracking line
+  /// numbers. A word starting with `#` comments out the rest of its line.
+  pub fn tokenize(contents: &str, source: Option<&str>) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+
+    for (line_index, line) in contents.lines().enumerate() {
+      for text in line.split_whitespace().take_while(|text| !text.starts_with('#')) {
+        tokens.push(Token {
+          text: text.to_string(),
+          span: Span {
+            index: tokens.len() + 1,
+            source: source.map(String::from),
+            line: Some(line_index + 1),
+          },
+        });
+      }
+    }
+
+    tokens
+  }
+}
+
+impl fmt::Display for Span {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "op {}", self.index)?;
+    match (&self.source, self.line) {
+      (Some(source), Some(line)) => write!(f, ", {source}:{line}"),
+      (None, Some(line)) => write!(f, ", line {line}"),
+      _ => Ok(()),
+    }
+  }
+}
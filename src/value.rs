@@ -0,0 +1,263 @@
+use std::fmt;
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_rational::BigRational;
+use num_traits::{FromPrimitive, ToPrimitive, Zero};
+use serde::{Deserialize, Serialize};
+use crate::token::Token;
+
+/// Stack element value.
+///
+/// Values are parsed once when they are pushed onto the stack, and are only
+/// formatted as text when the stack is displayed. Integers that do not fit in
+/// an `i64` are stored as arbitrary-precision integers (`Big`), and exact
+/// fractions are stored as rational numbers (`Ratio`, never an integer).
+/// Complex numbers (`Complex`) always have a non-zero imaginary part. Text
+/// values (`Str`, e.g., Roman numerals) are entered in double quotes and are
+/// not numbers. Quotations (`Block`) are unevaluated operations lists entered
+/// in square brackets (e.g., `[ dup x ]`) and evaluated by `call`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Value {
+  Int(i64),
+  Big(BigInt),
+  Ratio(BigRational),
+  Float(f64),
+  Complex(Complex64),
+  Str(String),
+  Block(Vec<Token>),
+}
+
+impl Value {
+  /// Parse a value from an operation string (`None` if the operation is not
+  /// a recognized value).
+  pub fn parse(op: &str) -> Option<Value> {
+    if let Ok(i) = op.parse::<i64>() {
+      return Some(Value::Int(i));
+    }
+    if let Ok(i) = op.parse::<BigInt>() {
+      return Some(Value::from(i));
+    }
+    if let Some(i) = Value::parse_radix(op) {
+      return Some(Value::from(i));
+    }
+    if let Some((numer, denom)) = op.split_once('/') { // fraction (e.g., 1/3)
+      if let (Ok(numer), Ok(denom)) = (numer.parse::<BigInt>(), denom.parse::<BigInt>()) {
+        if !denom.is_zero() {
+          return Some(Value::from(BigRational::new(numer, denom)));
+        }
+      }
+    }
+    if let Ok(f) = op.parse::<f64>() {
+      return Some(Value::Float(f));
+    }
+    if let Some(z) = Value::parse_complex(op) {
+      return Some(Value::from(z));
+    }
+    if let Some(value) = Value::parse_si(op) {
+      return Some(value);
+    }
+    if let Some(text) = op.strip_prefix('"').and_then(|op| op.strip_suffix('"')) {
+      return Some(Value::Str(text.to_string()));
+    }
+    None
+  }
+
+  // parse number with SI prefix suffix (e.g., 4.7k, 100m, or 10u)
+  fn parse_si(op: &str) -> Option<Value> {
+    let suffix: char = op.chars().last()?;
+    let exponent: i32 = match suffix {
+      'y' => -24, 'z' => -21, 'a' => -18, 'f' => -15, 'p' => -12, 'n' => -9,
+      'u' | 'µ' => -6, 'm' => -3, 'k' => 3, 'M' => 6, 'G' => 9, 'T' => 12,
+      'P' => 15, 'E' => 18, 'Z' => 21, 'Y' => 24,
+      _ => return None,
+    };
+    let number: &str = &op[..op.len() - suffix.len_utf8()];
+    if number.is_empty() || number.contains(['e', 'E', 'i', 'n', 'N']) {
+      return None; // exponent, imaginary, or non-finite number
+    }
+
+    // integers with multiplier prefixes remain exact
+    if let (Ok(i), true) = (number.parse::<BigInt>(), exponent > 0) {
+      return Some(Value::from(i * BigInt::from(10).pow(exponent as u32)));
+    }
+    format!("{number}e{exponent}").parse::<f64>().ok().map(Value::Float)
+  }
+
+  // parse hexadecimal, binary, or octal integer literal (e.g., 0xff, 0b1010,
+  // or -0o755)
+  fn parse_radix(op: &str) -> Option<BigInt> {
+    let (sign, digits) = match op.strip_prefix('-') {
+      Some(rest) => (-1, rest),
+      None => (1, op),
+    };
+    let (radix, digits) = match digits.get(..2)? {
+      "0x" | "0X" => (16, &digits[2..]),
+      "0b" | "0B" => (2, &digits[2..]),
+      "0o" | "0O" => (8, &digits[2..]),
+      _ => return None,
+    };
+    if digits.is_empty() || digits.starts_with(['+', '-']) || digits.contains('_') {
+      return None;
+    }
+
+    BigInt::parse_bytes(digits.as_bytes(), radix).map(|i| i * sign)
+  }
+
+  // parse complex number literal (e.g., 3+4i, 1.5-2i, or 4i)
+  fn parse_complex(op: &str) -> Option<Complex64> {
+    let body: &str = op.strip_suffix('i')?;
+
+    // split at the sign of the imaginary part (not a leading or exponent sign)
+    let split: Option<usize> = body.char_indices()
+      .rev()
+      .find(|(i, c)| (*c == '+' || *c == '-') && *i > 0 && !body[..*i].ends_with(['e', 'E']))
+      .map(|(i, _)| i);
+    let (re, im) = match split {
+      Some(i) => (body[..i].parse::<f64>().ok()?, &body[i..]),
+      None => (0.0, body),
+    };
+    if im.starts_with(['+', '-']) && im.len() == 1 {
+      return None; // imaginary part without digits
+    }
+    let im: f64 = im.parse::<f64>().ok()?;
+
+    Some(Complex64::new(re, im))
+  }
+
+  /// Value as a floating point number.
+  pub fn to_f64(&self) -> f64 {
+    match self {
+      Value::Int(i) => *i as f64,
+      Value::Big(i) => i.to_f64().unwrap_or(f64::NAN),
+      Value::Ratio(r) => r.to_f64().unwrap_or(f64::NAN),
+      Value::Float(f) => *f,
+      Value::Complex(_) | Value::Str(_) | Value::Block(_) => f64::NAN,
+    }
+  }
+
+  /// Value as an unsigned integer (`None` if the value is negative, has a
+  /// fractional part, or is too large).
+  pub fn to_u64(&self) -> Option<u64> {
+    match self {
+      Value::Int(i) => u64::try_from(*i).ok(),
+      Value::Big(i) => i.to_u64(),
+      Value::Ratio(_) | Value::Complex(_) | Value::Str(_) | Value::Block(_) => None,
+      Value::Float(f) => {
+        if f.is_finite() && *f >= 0.0 && f.fract() == 0.0 && *f <= u64::MAX as f64 {
+          Some(*f as u64)
+        } else {
+          None
+        }
+      },
+    }
+  }
+
+  /// Value as an exact integer (`None` if the value is a fraction or a
+  /// floating point number).
+  pub fn to_bigint(&self) -> Option<BigInt> {
+    match self {
+      Value::Int(i) => Some(BigInt::from(*i)),
+      Value::Big(i) => Some(i.clone()),
+      Value::Ratio(_) | Value::Float(_) | Value::Complex(_) | Value::Str(_) | Value::Block(_) => None,
+    }
+  }
+
+  /// Value as an exact rational number (`None` if the value is a floating
+  /// point number).
+  pub fn to_rational(&self) -> Option<BigRational> {
+    match self {
+      Value::Ratio(r) => Some(r.clone()),
+      _ => self.to_bigint().map(BigRational::from_integer),
+    }
+  }
+
+  /// Value as a complex number.
+  pub fn to_complex(&self) -> Complex64 {
+    match self {
+      Value::Complex(z) => *z,
+      _ => Complex64::new(self.to_f64(), 0.0),
+    }
+  }
+
+  /// Whether the value is neither NaN nor infinite (exact values are always
+  /// finite).
+  pub fn is_finite(&self) -> bool {
+    match self {
+      Value::Float(f) => f.is_finite(),
+      Value::Complex(z) => z.is_finite(),
+      _ => true,
+    }
+  }
+
+  /// Value as an integer, including floating point numbers without a
+  /// fractional part (`None` otherwise).
+  pub fn to_integer(&self) -> Option<BigInt> {
+    match self {
+      Value::Float(f) if f.fract() == 0.0 => BigInt::from_f64(*f),
+      Value::Float(_) | Value::Complex(_) | Value::Str(_) | Value::Block(_) => None,
+      _ => self.to_bigint(),
+    }
+  }
+}
+
+impl From<BigInt> for Value {
+  // integers are stored as i64 values when they fit
+  fn from(i: BigInt) -> Value {
+    match i.to_i64() {
+      Some(i) => Value::Int(i),
+      None => Value::Big(i),
+    }
+  }
+}
+
+impl From<BigRational> for Value {
+  // rational numbers are stored as integers when the denominator is one
+  fn from(r: BigRational) -> Value {
+    if r.is_integer() {
+      Value::from(r.to_integer())
+    } else {
+      Value::Ratio(r)
+    }
+  }
+}
+
+impl From<Complex64> for Value {
+  // complex numbers are stored as floating point numbers when the imaginary
+  // part is zero
+  fn from(z: Complex64) -> Value {
+    if z.im == 0.0 {
+      Value::Float(z.re)
+    } else {
+      Value::Complex(z)
+    }
+  }
+}
+
+// format complex number using the given part formatter (e.g., 3+4i)
+pub(crate) fn format_complex(z: &Complex64, part: impl Fn(f64) -> String) -> String {
+  match (z.re == 0.0, z.im.is_sign_negative()) {
+    (true, _) => format!("{}i", part(z.im)),
+    (false, true) => format!("{}-{}i", part(z.re), part(-z.im)),
+    (false, false) => format!("{}+{}i", part(z.re), part(z.im)),
+  }
+}
+
+impl fmt::Display for Value {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Value::Int(i) => write!(f, "{i}"),
+      Value::Big(i) => write!(f, "{i}"),
+      Value::Ratio(r) => write!(f, "{r}"),
+      Value::Float(x) => write!(f, "{x}"),
+      Value::Complex(z) => write!(f, "{}", format_complex(z, |x| x.to_string())),
+      Value::Str(text) => write!(f, "{text}"),
+      Value::Block(fops) => {
+        write!(f, "[")?;
+        for fop in fops {
+          write!(f, " {}", fop.text)?;
+        }
+        write!(f, " ]")
+      },
+    }
+  }
+}
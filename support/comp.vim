@@ -36,6 +36,7 @@ syn match compNumber '\<-\=\d*[.]\=\d\+[DdEe][-+]\d\+\>'
 " Comments
 syn match compComment '\.(\s[^)]*)' contains=compTodo
 syn region compComment start='\(^\|\s\)\zs(\s' skip='\\)' end=')' contains=compTodo
+syn match compComment '\(^\|\s\)\zs#.*$' contains=compTodo
 
 
 " Define the default highlighting.